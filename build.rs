@@ -1,44 +1,50 @@
 use heck::{ToPascalCase, ToSnakeCase};
 use quote::{format_ident, quote};
-use serde::Deserialize;
 use std::env;
 use std::fs;
 use std::path::Path;
 
-// Structures for parsing TOML
-#[derive(Debug, Deserialize)]
-struct CompilerConfig {
-    name: String,
-    description: String,
-    working_dir: String,
-    parameters: Vec<ParameterConfig>,
+// Structures for parsing TOML, shared with the `dynamic` feature of the library.
+#[path = "src/config.rs"]
+mod config;
+use config::{resolve_games, ArgumentRef, CompilerConfig, ValueType};
+
+/// The schema of `games.toml`, the registry behind the generated `SourceGame` enum.
+#[derive(Debug, serde::Deserialize)]
+struct GameRegistry {
+    games: Vec<GameEntry>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-struct ParameterConfig {
+#[derive(Debug, serde::Deserialize)]
+struct GameEntry {
+    id: String,
     name: String,
-    description: String,
-    argument: String,
-    value_type: ValueType,
-    default_value: Option<String>,
+    app_id: u32,
+    game_dir: String,
+    #[serde(default = "GameEntry::default_tools_dir")]
+    tools_dir: String,
     #[serde(default)]
-    is_default: bool,
-    constraints: Option<ConstraintsConfig>,
+    aliases: Vec<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-struct ConstraintsConfig {
-    compatible_games: Option<Vec<u32>>,
+impl GameEntry {
+    fn default_tools_dir() -> String {
+        "bin".to_string()
+    }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-enum ValueType {
-    Flag,
-    Float,
-    Integer,
-    String,
-    Path,
+impl GameRegistry {
+    /// Finds the App ID of a game by its id, an alias or its display name (case-insensitive).
+    fn lookup(&self, name: &str) -> Option<u32> {
+        self.games
+            .iter()
+            .find(|game| {
+                game.id.eq_ignore_ascii_case(name)
+                    || game.name.eq_ignore_ascii_case(name)
+                    || game.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+            })
+            .map(|game| game.app_id)
+    }
 }
 
 fn main() {
@@ -49,8 +55,11 @@ fn main() {
     let mut compiler_modules = Vec::new();
     let mut compiler_metadata = Vec::new();
 
-    // Find all TOML files in the directory
     let manifest_dir = std::path::PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let games = load_game_registry(&manifest_dir.join("games.toml"));
+    let lookup_game = |name: &str| games.lookup(name);
+
+    // Find all TOML files in the directory
     let compilers_configs_dir = manifest_dir.join("compiler_configs");
     if !compilers_configs_dir.exists() || !compilers_configs_dir.is_dir() {
         panic!("'compiler_configs' directory not found at {:?} or is not a directory.", compilers_configs_dir);
@@ -84,19 +93,20 @@ fn main() {
             },
         };
 
-        if config.parameters.is_empty() {
-            eprintln!("File: {} does not contain any parameters. Skipping.", path.display());
-            continue;
-        }
+        validate_config(&config, &path, &lookup_game);
 
         let module_name_str = config.name.to_snake_case();
         let struct_name_str = config.name.to_pascal_case();
 
-        compiler_modules.push(generate_compiler_module(&config));
+        compiler_modules.push(generate_compiler_module(&config, &lookup_game));
         compiler_metadata.push((struct_name_str, module_name_str));
     }
 
+    // Hand-written in src/custom.rs, for programs without a config.
+    compiler_metadata.push(("Custom".to_string(), "custom".to_string()));
+
     let compiler_enum_token_stream = generate_compiler_enum(&compiler_metadata);
+    let game_registry_token_stream = generate_game_registry(&games);
 
     // Assemble everything together
     let final_code = quote! {
@@ -104,6 +114,7 @@ fn main() {
 
         #(#compiler_modules)*
         #compiler_enum_token_stream
+        #game_registry_token_stream
     };
 
     let syntax_tree = match syn::parse2::<syn::File>(final_code) {
@@ -119,10 +130,66 @@ fn main() {
     let formatted_code = prettyplease::unparse(&syntax_tree);
     fs::write(&dest_path, formatted_code).unwrap();
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/config.rs");
+    println!("cargo:rerun-if-changed=games.toml");
+}
+
+/// Reads `games.toml` and checks that ids, names and App IDs are unique.
+fn load_game_registry(path: &Path) -> GameRegistry {
+    let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read game registry at {:?}: {}", path, e));
+    let registry: GameRegistry = toml::from_str(&content)
+        .unwrap_or_else(|e| panic!("\n\n[BUILD SCRIPT ERROR] Invalid game registry {}\nError: {}\n", path.display(), e));
+
+    for (index, game) in registry.games.iter().enumerate() {
+        let names = std::iter::once(&game.id).chain(&game.aliases).chain(std::iter::once(&game.name));
+        for name in names {
+            if registry.lookup(name) != Some(game.app_id) {
+                panic!("\n\n[BUILD SCRIPT ERROR] Game name '{}' in {} is already used by another game\n", name, path.display());
+            }
+        }
+        if registry.games[..index].iter().any(|other| other.app_id == game.app_id) {
+            panic!("\n\n[BUILD SCRIPT ERROR] App ID {} in {} is registered twice\n", game.app_id, path.display());
+        }
+    }
+    registry
+}
+
+/// Checks constraints that serde cannot express and aborts the build with a readable error.
+fn validate_config(config: &CompilerConfig, path: &Path, lookup_game: &dyn Fn(&str) -> Option<u32>) {
+    if let Err((parameter, reason)) = config.validate(lookup_game) {
+        panic!(
+            "\n\n[BUILD SCRIPT ERROR] Invalid constraints in {}\nParameter: '{}'\nReason:    {}\n",
+            path.display(),
+            parameter,
+            reason
+        )
+    }
+}
+
+/// Turns a numeric bound into a literal of the parameter's Rust type (`f32` or `i64`).
+fn bound_literal(value_type: ValueType, bound: f64) -> proc_macro2::TokenStream {
+    match value_type {
+        ValueType::Float => {
+            let val = bound as f32;
+            quote! { #val }
+        }
+        ValueType::Integer => {
+            let val = bound as i64;
+            quote! { #val }
+        }
+        _ => unreachable!("bounds are validated to belong to numeric parameters"),
+    }
+}
+
+/// Builds a `ValueRange { .. }` expression from optional bounds.
+fn value_range_tokens(min: Option<f64>, max: Option<f64>) -> proc_macro2::TokenStream {
+    let min = match min { Some(v) => quote! { Some(#v) }, None => quote! { None } };
+    let max = match max { Some(v) => quote! { Some(#v) }, None => quote! { None } };
+    quote! { ValueRange { min: #min, max: #max } }
 }
 
 /// Generates a module for a single compiler (e.g., `vbsp`).
-fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream {
+fn generate_compiler_module(config: &CompilerConfig, lookup_game: &dyn Fn(&str) -> Option<u32>) -> proc_macro2::TokenStream {
     //=========================================================================================
     // STEP 1: IDENTIFIERS & METADATA
     // - Create the necessary identifiers (names for the module, structs, enums).
@@ -134,6 +201,12 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
     let name_str = &config.name;
     let description_str = &config.description;
     let working_dir_template = &config.working_dir;
+    let base_arguments = config.base_arguments.split_whitespace();
+    let executable = config.executable_or_default();
+    let (exe_windows, exe_linux, exe_macos, exe_search_dirs) =
+        (&executable.windows, &executable.linux, &executable.macos, &executable.search_dirs);
+    // An empty enum can only be matched by dereferencing, e.g. `match *self {}`.
+    let match_self = if config.parameters.is_empty() { quote! { *self } } else { quote! { self } };
     let arg_doc_comment = format!("Enum of arguments for {}", struct_name);
 
 
@@ -168,7 +241,7 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                 ValueType::Flag => quote! { #arg_enum_name::#variant_ident },
                 _ => {
                     let default_val_str = p.default_value.as_ref()
-                        .expect(&format!("Parameter '{}' needs a default_value", p.name));
+                        .unwrap_or_else(|| panic!("Parameter '{}' needs a default_value", p.name));
                     let value = match p.value_type {
                         ValueType::Float => {
                             let val: f32 = default_val_str.parse().expect("Invalid float");
@@ -242,6 +315,13 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
         Some(quote! { Self::#variant { .. } => Some(#arg_enum_name::#variant(#value)), })
     });
 
+    // --- `argument()` method arms ---
+    let argument_arms = config.parameters.iter().map(|p| {
+        let variant = format_ident!("{}", p.name.to_pascal_case());
+        let argument = &p.argument;
+        quote! { Self::#variant { .. } => #argument, }
+    });
+
     // --- `as_arg()` method arms ---
     let as_arg_arms = config.parameters.iter().map(|p| {
         let variant = format_ident!("{}", p.name.to_pascal_case());
@@ -273,22 +353,59 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
         .filter_map(|p| {
             let variant = format_ident!("{}", p.name.to_pascal_case());
             p.constraints.as_ref().and_then(|c| c.compatible_games.as_ref()).map(|games| {
+                let games = resolve_games(games, lookup_game).expect("game names are validated before generation");
                 quote! { Self::#variant { .. } => Some(&[#(#games),*]), }
             })
         });
 
+    // --- `incompatible_games()` method arms ---
+    let incompatible_games_arms = config.parameters.iter()
+        .filter_map(|p| {
+            let variant = format_ident!("{}", p.name.to_pascal_case());
+            p.constraints.as_ref().and_then(|c| c.incompatible_games.as_ref()).map(|games| {
+                let games = resolve_games(games, lookup_game).expect("game names are validated before generation");
+                quote! { Self::#variant { .. } => Some(&[#(#games),*]), }
+            })
+        });
+
+    // --- `requires()` / `conflicts_with()` method arms ---
+    let requires_arms = config.parameters.iter()
+        .filter(|p| !p.requires.is_empty())
+        .map(|p| {
+            let variant = format_ident!("{}", p.name.to_pascal_case());
+            let requires = &p.requires;
+            quote! { Self::#variant { .. } => &[#(#requires),*], }
+        });
+    let conflicts_with_arms = config.parameters.iter()
+        .filter(|p| !p.conflicts_with.is_empty())
+        .map(|p| {
+            let variant = format_ident!("{}", p.name.to_pascal_case());
+            let conflicts = &p.conflicts_with;
+            quote! { Self::#variant { .. } => &[#(#conflicts),*], }
+        });
+
+    // --- `value_range()` method arms ---
+    let value_range_arms = config.parameters.iter()
+        .filter_map(|p| {
+            let (min, max) = p.value_bounds()?;
+            let variant = format_ident!("{}", p.name.to_pascal_case());
+            let range = value_range_tokens(min, max);
+            Some(quote! { Self::#variant { .. } => Some(#range), })
+        });
+
 
     //=========================================================================================
     // STEP 5: `TRYFROM<&STR>` TRAIT IMPLEMENTATION
-    // - Generate the `match` arms for parsing string inputs into `Arg` enum variants.
+    // - Generate the parsing logic that turns an optional value string into an `Arg` variant.
+    //   It is shared by `TryFrom<&str>` (keyed by argument) and `from_parameter` (keyed by index).
     //=========================================================================================
-    let try_from_arms = config.parameters.iter().map(|p| {
+    let parse_bodies: Vec<_> = config.parameters.iter().map(|p| {
         let arg_str = &p.argument;
         let variant_ident = format_ident!("{}", p.name.to_pascal_case());
 
         match p.value_type {
             ValueType::Flag => quote! {
-                #arg_str => {
+                {
                     if value_opt.is_some() {
                         return Err(ParseArgError::UnexpectedValue(#arg_str));
                     }
@@ -304,29 +421,127 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                     ValueType::Flag => unreachable!(),
                 };
 
+                // Bounds are compared in the parameter's own type, so `0.1` as an `f32`
+                // is not rejected by a `max_value = 0.1` that was written as an `f64`.
+                // `contains` is false for NaN, matching `ValueRange::contains`.
+                let range_check = p.value_bounds().map(|(min, max)| {
+                    let range = value_range_tokens(min, max);
+                    let min = min.map(|min| bound_literal(p.value_type, min));
+                    let max = max.map(|max| bound_literal(p.value_type, max));
+                    let bounds = match (min, max) {
+                        (Some(min), Some(max)) => quote! { (#min..=#max) },
+                        (Some(min), None) => quote! { (#min..) },
+                        (None, Some(max)) => quote! { (..=#max) },
+                        (None, None) => quote! { (..) },
+                    };
+                    quote! {
+                        if !#bounds.contains(&parsed_val) {
+                            return Err(ParseArgError::OutOfRange {
+                                argument: #arg_str,
+                                value: value_str.to_string(),
+                                range: #range,
+                            });
+                        }
+                    }
+                });
+
                 quote! {
-                    #arg_str => {
+                    {
                         let value_str = value_opt.ok_or(ParseArgError::MissingValue(#arg_str))?;
                         let parsed_val = #parse_logic.map_err(|_| ParseArgError::InvalidValue {
                             argument: #arg_str,
                             value: value_str.to_string(),
                         })?;
+                        #range_check
                         Ok(Self::#variant_ident(parsed_val))
                     }
                 }
             }
         }
+    }).collect();
+
+    let try_from_arms = config.parameters.iter().zip(&parse_bodies).map(|(p, body)| {
+        let arg_str = &p.argument;
+        quote! { #arg_str => #body }
+    });
+
+    let from_parameter_arms = parse_bodies.iter().enumerate().map(|(index, body)| {
+        quote! { #index => #body }
+    });
+
+    // --- `parameters()` table, in declaration order ---
+    let parameter_specs = config.parameters.iter().map(|p| {
+        let name = &p.name;
+        let argument = &p.argument;
+        let vt_ident = match p.value_type {
+            ValueType::Flag => format_ident!("Flag"),
+            ValueType::Float => format_ident!("Float"),
+            ValueType::Integer => format_ident!("Integer"),
+            ValueType::String => format_ident!("String"),
+            ValueType::Path => format_ident!("Path"),
+        };
+        quote! { ParameterSpec { name: #name, argument: #argument, value_type: ValueType::#vt_ident } }
     });
 
 
+    // --- `default_for_game()`: `[[game_defaults]]` applied on top of `Default` ---
+    let game_default_blocks: Vec<_> = config.game_defaults.iter().map(|set| {
+        let games = resolve_games(&set.games, lookup_game).expect("game names are validated before generation");
+        let changes = argument_changes(config, &arg_enum_name, &quote! { compiler.selected_args }, &set.add, &set.remove);
+        quote! {
+            if [#(#games),*].contains(&app_id) {
+                #changes
+            }
+        }
+    }).collect();
+    let app_id_param = if game_default_blocks.is_empty() { format_ident!("_app_id") } else { format_ident!("app_id") };
+
+    // --- `presets()` / `apply_preset()`: `[[presets]]` applied on top of the current arguments ---
+    let preset_specs = config.presets.iter().map(|preset| {
+        let (name, description) = (&preset.name, &preset.description);
+        quote! { PresetSpec { name: #name, description: #description } }
+    });
+    let apply_preset_body = if config.presets.is_empty() {
+        quote! { let _ = name; false }
+    } else {
+        let arms = config.presets.iter().enumerate().map(|(index, preset)| {
+            let changes = argument_changes(config, &arg_enum_name, &quote! { self.selected_args }, &preset.add, &preset.remove);
+            quote! { Some(#index) => { #changes } }
+        });
+        quote! {
+            match self.presets().iter().position(|preset| preset.name.eq_ignore_ascii_case(name)) {
+                #(#arms)*
+                _ => return false,
+            }
+            true
+        }
+    };
+
+    // Compilers without parameters never look at the value part.
+    let value_opt_param = if config.parameters.is_empty() { format_ident!("_value_opt") } else { format_ident!("value_opt") };
+    let split_key_value = if config.parameters.is_empty() {
+        quote! { let key = value.split_once(' ').map_or(value, |(k, _)| k); }
+    } else {
+        quote! {
+            let (key, value_opt) =
+                if let Some((k, v)) = value.split_once(' ') {
+                    (k, Some(v))
+                } else {
+                    (value, None)
+                };
+        }
+    };
+
+
     //=========================================================================================
     // STEP 6: FINAL ASSEMBLY
     // - Combine all the generated token streams into the final module structure.
     //=========================================================================================
     quote! {
         #[doc = "This module is auto-generated by build.rs."]
+        #[allow(clippy::match_like_matches_macro, clippy::match_single_binding, clippy::derivable_impls)]
         pub mod #module_name {
-            use crate::{Compiler, CompilerArg, ValueType, ParseArgError};
+            use crate::{Compiler, CompilerArg, ExecutableSpec, ValueType, ValueRange, ParameterSpec, PresetSpec, ParseArgError};
             use std::fmt;
 
             #[doc = #description_str]
@@ -343,6 +558,25 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                 fn name(&self) -> &'static str { #name_str }
                 fn description(&self) -> &'static str { #description_str }
                 fn working_dir_template(&self) -> &'static str { #working_dir_template }
+                fn base_arguments(&self) -> &'static [&'static str] { &[#(#base_arguments),*] }
+                fn executable(&self) -> ExecutableSpec {
+                    ExecutableSpec {
+                        windows: &[#(#exe_windows),*],
+                        linux: &[#(#exe_linux),*],
+                        macos: &[#(#exe_macos),*],
+                        search_dirs: &[#(#exe_search_dirs),*],
+                    }
+                }
+
+                fn parameters(&self) -> &'static [ParameterSpec] { #arg_enum_name::parameters() }
+                fn parse_parameter(&self, index: usize, value: Option<&str>) -> Result<Self::Arg, ParseArgError> {
+                    #arg_enum_name::from_parameter(index, value)
+                }
+
+                fn presets(&self) -> &'static [PresetSpec] { &[#(#preset_specs),*] }
+                fn apply_preset(&mut self, name: &str) -> bool {
+                    #apply_preset_body
+                }
 
                 fn get_args(&self) -> &[Self::Arg] { &self.selected_args }
                 fn add_arg(&mut self, arg: Self::Arg) { self.selected_args.push(arg); }
@@ -365,6 +599,30 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                         selected_args: Vec::new()
                     }
                 }
+
+                #[doc = "Creates an instance with the default arguments for the game `app_id`:"]
+                #[doc = "the `is_default` arguments, adjusted by the config's `[[game_defaults]]` sets."]
+                pub fn default_for_game(#app_id_param: u32) -> Self {
+                    #[allow(unused_mut)]
+                    let mut compiler = Self::default();
+                    #(#game_default_blocks)*
+                    compiler
+                }
+            }
+
+            impl #arg_enum_name {
+                #[doc = "Returns every parameter declared in the config, in declaration order."]
+                pub fn parameters() -> &'static [ParameterSpec] {
+                    &[#(#parameter_specs),*]
+                }
+
+                #[doc = "Builds the argument for the parameter at `index` in `parameters()`."]
+                pub fn from_parameter(index: usize, #value_opt_param: Option<&str>) -> Result<Self, ParseArgError> {
+                    match index {
+                        #(#from_parameter_arms,)*
+                        _ => Err(ParseArgError::UnknownArgument(format!("#{}", index))),
+                    }
+                }
             }
 
             #[doc = #arg_doc_comment]
@@ -378,10 +636,10 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
             // Implementation of the `CompilerArg` trait for the `Arg` enum.
             impl CompilerArg for #arg_enum_name {
                 fn name(&self) -> &'static str {
-                    match self { #(#name_arms)* }
+                    match #match_self { #(#name_arms)* }
                 }
                 fn description(&self) -> &'static str {
-                    match self { #(#desc_arms)* }
+                    match #match_self { #(#desc_arms)* }
                 }
                 fn value_type(&self) -> ValueType {
                     match self {
@@ -395,8 +653,11 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                         _ => None,
                     }
                 }
+                fn argument(&self) -> &'static str {
+                    match #match_self { #(#argument_arms)* }
+                }
                 fn as_arg(&self) -> (&'static str, Option<String>) {
-                    match self { #(#as_arg_arms)* }
+                    match #match_self { #(#as_arg_arms)* }
                 }
                 fn is_default(&self) -> bool {
                     match self {
@@ -410,6 +671,30 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                         _ => None,
                     }
                 }
+                fn incompatible_games(&self) -> Option<&'static [u32]> {
+                    match self {
+                        #(#incompatible_games_arms)*
+                        _ => None,
+                    }
+                }
+                fn value_range(&self) -> Option<ValueRange> {
+                    match self {
+                        #(#value_range_arms)*
+                        _ => None,
+                    }
+                }
+                fn requires(&self) -> &'static [&'static str] {
+                    match self {
+                        #(#requires_arms)*
+                        _ => &[],
+                    }
+                }
+                fn conflicts_with(&self) -> &'static [&'static str] {
+                    match self {
+                        #(#conflicts_with_arms)*
+                        _ => &[],
+                    }
+                }
             }
 
             // `Display` implementation for convenient printing.
@@ -424,12 +709,7 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
                 type Error = ParseArgError;
 
                 fn try_from(value: &'a str) -> Result<Self, Self::Error> {
-                    let (key, value_opt) =
-                        if let Some((k, v)) = value.split_once(' ') {
-                            (k, Some(v))
-                        } else {
-                            (value, None)
-                        };
+                    #split_key_value
 
                     match key {
                         #(#try_from_arms,)*
@@ -448,6 +728,38 @@ fn generate_compiler_module(config: &CompilerConfig) -> proc_macro2::TokenStream
     }
 }
 
+/// Generates the statements that apply an `add`/`remove` list from the config to `target`
+/// (a `Vec` of the argument enum). Added arguments replace existing ones with the same switch;
+/// positionals cannot be matched, so they are only appended.
+fn argument_changes(
+    config: &CompilerConfig,
+    arg_enum_name: &proc_macro2::Ident,
+    target: &proc_macro2::TokenStream,
+    add: &[ArgumentRef],
+    remove: &[String],
+) -> proc_macro2::TokenStream {
+    let removed: Vec<&str> = remove.iter().map(String::as_str)
+        .chain(add.iter().map(|r| r.argument()).filter(|a| !a.is_empty()))
+        .collect();
+    let retain = (!removed.is_empty()).then(|| quote! {
+        #target.retain(|arg| ![#(#removed),*].contains(&arg.argument()));
+    });
+    let additions = add.iter().map(|reference| {
+        let (index, value) = config.resolve_argument(reference).expect("argument changes are validated before generation");
+        let value = match value {
+            Some(value) => quote! { Some(#value) },
+            None => quote! { None },
+        };
+        quote! {
+            #target.push(#arg_enum_name::from_parameter(#index, #value).expect("argument changes are validated at build time"));
+        }
+    });
+    quote! {
+        #retain
+        #(#additions)*
+    }
+}
+
 /// Generates the main enum that combines all compilers.
 fn generate_compiler_enum(metadata: &[(String, String)]) -> proc_macro2::TokenStream {
     let variants = metadata.iter().map(|(struct_name, module_name)| {
@@ -471,11 +783,67 @@ fn generate_compiler_enum(metadata: &[(String, String)]) -> proc_macro2::TokenSt
         quote! { Self::#struct_ident(inner) => inner.build_args(), }
     });
 
+    let validate_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.validate(), }
+    });
+
+    let validate_for_game_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.validate_for_game(app_id), }
+    });
+
     let build_command_arms = metadata.iter().map(|(struct_name, _)| {
         let struct_ident = format_ident!("{}", struct_name);
         quote! { Self::#struct_ident(inner) => inner.build_command(context, executable), }
     });
 
+    let presets_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.presets(), }
+    });
+
+    let apply_preset_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.apply_preset(name), }
+    });
+
+    let base_arguments_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.base_arguments(), }
+    });
+
+    let executable_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! { Self::#struct_ident(inner) => inner.executable(), }
+    });
+
+    let empty_instances = metadata.iter().map(|(struct_name, module_name)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        let module_ident = format_ident!("{}", module_name);
+        quote! { Self::#struct_ident(#module_ident::#struct_ident::new()), }
+    });
+
+    let parse_command_line_arms = metadata.iter().map(|(struct_name, _)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        quote! {
+            Self::#struct_ident(inner) => {
+                inner.clear_args();
+                inner.extend_from_args(&tokens)?;
+            }
+        }
+    });
+
+    let from_impls = metadata.iter().map(|(struct_name, module_name)| {
+        let struct_ident = format_ident!("{}", struct_name);
+        let module_ident = format_ident!("{}", module_name);
+        quote! {
+            impl From<#module_ident::#struct_ident> for CompilerEnum {
+                fn from(compiler: #module_ident::#struct_ident) -> Self { Self::#struct_ident(compiler) }
+            }
+        }
+    });
+
     let use_statements = metadata.iter().map(|(_, module_name)| {
         let mod_name = format_ident!("{}", module_name);
         quote! { pub use #mod_name::*; }
@@ -502,9 +870,147 @@ fn generate_compiler_enum(metadata: &[(String, String)]) -> proc_macro2::TokenSt
             pub fn build_args(&self) -> Vec<String> {
                 match self { #(#build_args_arms)* }
             }
+            pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+                match self { #(#validate_arms)* }
+            }
+            pub fn validate_for_game(&self, app_id: u32) -> Vec<Diagnostic> {
+                match self { #(#validate_for_game_arms)* }
+            }
             pub fn build_command(&self, context: &CompilerContext, executable: Option<PathBuf>) -> CommandInfo {
                 match self { #(#build_command_arms)* }
             }
+            pub fn base_arguments(&self) -> &'static [&'static str] {
+                match self { #(#base_arguments_arms)* }
+            }
+            pub fn executable(&self) -> ExecutableSpec {
+                match self { #(#executable_arms)* }
+            }
+            #[doc = "Returns an empty instance of every compiler whose config lists `file_name` as an executable"]
+            #[doc = "(see [`ExecutableSpec::matches`]). Compilers with base arguments come first, as they are"]
+            #[doc = "more specific than another compiler sharing the same executable."]
+            pub fn for_executable(file_name: &str) -> Vec<Self> {
+                let mut compilers: Vec<Self> = vec![#(#empty_instances)*]
+                    .into_iter()
+                    .filter(|compiler| compiler.executable().matches(file_name))
+                    .collect();
+                compilers.sort_by_key(|compiler| std::cmp::Reverse(compiler.base_arguments().len()));
+                compilers
+            }
+            #[doc = "Parses `line` into a copy of this compiler with its arguments replaced,"]
+            #[doc = "see [`Compiler::from_command_line`]."]
+            pub fn parse_command_line(&self, line: &str) -> Result<Self, ParseArgError> {
+                let tokens = command_line::split_windows(line);
+                let mut compiler = self.clone();
+                match &mut compiler { #(#parse_command_line_arms)* }
+                Ok(compiler)
+            }
+            pub fn presets(&self) -> &'static [PresetSpec] {
+                match self { #(#presets_arms)* }
+            }
+            pub fn apply_preset(&mut self, name: &str) -> bool {
+                match self { #(#apply_preset_arms)* }
+            }
+        }
+
+        #(#from_impls)*
+    }
+}
+
+/// Generates the `SourceGame` enum from `games.toml`.
+fn generate_game_registry(registry: &GameRegistry) -> proc_macro2::TokenStream {
+    let variants: Vec<_> = registry.games.iter().map(|game| format_ident!("{}", game.id.to_pascal_case())).collect();
+
+    let variant_defs = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let doc = format!("{} (App ID {}).", game.name, game.app_id);
+        quote! { #[doc = #doc] #variant, }
+    });
+    let app_id_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let app_id = game.app_id;
+        quote! { Self::#variant => #app_id, }
+    });
+    let id_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let id = &game.id;
+        quote! { Self::#variant => #id, }
+    });
+    let name_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let name = &game.name;
+        quote! { Self::#variant => #name, }
+    });
+    let game_dir_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let game_dir = &game.game_dir;
+        quote! { Self::#variant => #game_dir, }
+    });
+    let tools_dir_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let tools_dir = &game.tools_dir;
+        quote! { Self::#variant => #tools_dir, }
+    });
+    let aliases_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let aliases = &game.aliases;
+        quote! { Self::#variant => &[#(#aliases),*], }
+    });
+    let from_app_id_arms = registry.games.iter().zip(&variants).map(|(game, variant)| {
+        let app_id = game.app_id;
+        quote! { #app_id => Some(Self::#variant), }
+    });
+
+    quote! {
+        /// A Source game known to this crate, generated from `games.toml`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "enum_iter", derive(strum_macros::EnumIter))]
+        #[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+        pub enum SourceGame {
+            #(#variant_defs)*
+        }
+
+        impl SourceGame {
+            /// Every registered game, in registry order.
+            pub const ALL: &'static [SourceGame] = &[#(Self::#variants),*];
+
+            /// The Steam App ID of the game.
+            pub fn app_id(self) -> u32 {
+                match self { #(#app_id_arms)* }
+            }
+            /// The short name used in compiler configs, e.g. `"tf2"`.
+            pub fn id(self) -> &'static str {
+                match self { #(#id_arms)* }
+            }
+            /// The display name, e.g. `"Team Fortress 2"`.
+            pub fn name(self) -> &'static str {
+                match self { #(#name_arms)* }
+            }
+            /// The mod folder inside the install directory, e.g. `"tf"`.
+            pub fn game_dir(self) -> &'static str {
+                match self { #(#game_dir_arms)* }
+            }
+            /// The folder holding the compilers, relative to the install directory.
+            pub fn tools_dir(self) -> &'static str {
+                match self { #(#tools_dir_arms)* }
+            }
+            /// Alternative names accepted by [`from_name`](Self::from_name).
+            pub fn aliases(self) -> &'static [&'static str] {
+                match self { #(#aliases_arms)* }
+            }
+            /// Looks up a game by its Steam App ID.
+            pub fn from_app_id(app_id: u32) -> Option<Self> {
+                match app_id {
+                    #(#from_app_id_arms)*
+                    _ => None,
+                }
+            }
+            /// Looks up a game by its id, an alias or its display name, ignoring case.
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|game| {
+                    game.id().eq_ignore_ascii_case(name)
+                        || game.name().eq_ignore_ascii_case(name)
+                        || game.aliases().iter().any(|alias| alias.eq_ignore_ascii_case(name))
+                })
+            }
+        }
+
+        impl std::fmt::Display for SourceGame {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.name())
+            }
         }
     }
 }
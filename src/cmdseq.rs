@@ -0,0 +1,657 @@
+//! Reading and writing Hammer's run configurations: the binary `CmdSeq.wc` and the
+//! KeyValues text format used by Hammer++ and newer Hammer branches.
+//!
+//! A file holds any number of named command sequences, each a list of steps from Hammer's
+//! "Run Map (Expert)" dialog. Steps that run a known compiler are parsed into a
+//! [`CompilerEnum`]; other programs and Hammer's built-in commands are kept verbatim, so a
+//! file survives a read/write round trip without losing any step.
+//!
+//! Hammer's macros (`$file`, `$path`, `$gamedir`, ...) are translated onto this crate's
+//! placeholders when a step is parsed and back when it is written, see [`from_hammer_macros`].
+//!
+//! The text format holds the same fields as the binary one:
+//!
+//! ```text
+//! "Command Sequences"
+//! {
+//!     "[Default]"
+//!     {
+//!         "1"
+//!         {
+//!             "enable"        "1"
+//!             "specialcmd"    "0"
+//!             "run"           "$bsp_exe"
+//!             "params"        "-game $gamedir $path\$file"
+//!             "ensurecheck"   "0"
+//!             "ensurefn"      ""
+//!             "useprocesswnd" "1"
+//!             "nowait"        "0"
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Like Hammer, the text format uses no escape sequences: backslashes are literal, and
+//! strings cannot contain `"`. Missing fields take the defaults of [`SequenceCommand::new`].
+
+use crate::command_line;
+use crate::keyvalues::{self, Block, KeyValuesError, Value};
+use crate::custom::Custom;
+use crate::{CommandInfo, CompilerContext, CompilerEnum, Pipeline, PipelineStage};
+use std::path::{Path, PathBuf};
+
+/// The signature at the start of every `CmdSeq.wc`.
+pub const SIGNATURE: &[u8] = b"Worldcraft Command Sequences\r\n\x1a";
+
+/// The file version written by [`to_bytes`].
+const VERSION: f32 = 0.2;
+
+/// `MAX_PATH`: the size of every string field of a command.
+const PATH_FIELD_LEN: usize = 260;
+/// The size of a sequence name.
+const NAME_FIELD_LEN: usize = 128;
+
+/// Errors that can occur while reading or writing a `CmdSeq.wc`.
+#[derive(Debug)]
+pub enum CmdSeqError {
+    /// The file could not be read or written.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The data does not start with [`SIGNATURE`], or a text file has no sequences block.
+    InvalidSignature,
+    /// A text file is not valid KeyValues.
+    Text(KeyValuesError),
+    /// The file version is neither 0.1 nor 0.2.
+    UnsupportedVersion(f32),
+    /// The data ended in the middle of a sequence.
+    UnexpectedEof,
+    /// A string does not fit into its fixed-size field.
+    FieldTooLong {
+        field: &'static str,
+        value: String,
+        max: usize,
+    },
+    /// A string contains a `"`, which the text format cannot store.
+    QuoteInText {
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for CmdSeqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to access '{}': {}", path.display(), source),
+            Self::InvalidSignature => write!(f, "not a Hammer command sequence file"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported command sequence version {}", version),
+            Self::Text(source) => write!(f, "invalid command sequence text: {}", source),
+            Self::UnexpectedEof => write!(f, "unexpected end of command sequence data"),
+            Self::FieldTooLong { field, value, max } => {
+                write!(f, "{} '{}' is longer than {} bytes", field, value, max)
+            }
+            Self::QuoteInText { field, value } => {
+                write!(f, "{} '{}' contains a double quote, which the text format cannot store", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CmdSeqError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Text(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A named list of steps, e.g. "[Default]" or "HDR Full Compile".
+#[derive(Debug, Clone)]
+pub struct CommandSequence {
+    pub name: String,
+    pub commands: Vec<SequenceCommand>,
+}
+
+/// One step of a [`CommandSequence`], with all the options of Hammer's dialog.
+#[derive(Debug, Clone)]
+pub struct SequenceCommand {
+    /// Whether the step is checked and runs.
+    pub enabled: bool,
+    /// The "Run" field as written in Hammer, e.g. `$light_exe` or a full path.
+    pub run: String,
+    pub step: SequenceStep,
+    /// Whether Hammer checks that `ensure_file` exists after the step ran.
+    pub ensure_check: bool,
+    /// The file checked by `ensure_check`, with Hammer macros.
+    pub ensure_file: String,
+    /// Obsolete, but kept so files round-trip unchanged.
+    pub long_filenames: bool,
+    /// Whether the output goes to Hammer's process window.
+    pub use_process_window: bool,
+    /// Whether Hammer starts the next step without waiting for this one.
+    pub no_wait: bool,
+    /// The parameters a compiler step was parsed from and the arguments they became,
+    /// so an unchanged step is written back exactly as it was read.
+    source: Option<(String, Vec<String>)>,
+}
+
+/// What a [`SequenceCommand`] does.
+#[derive(Debug, Clone)]
+pub enum SequenceStep {
+    /// Runs a known compiler. Hammer macros in its arguments are already translated.
+    Compiler(CompilerEnum),
+    /// Runs any other program. The parameters are kept as written in Hammer.
+    Program { parameters: String },
+    /// One of Hammer's built-in file commands. The parameters are kept as written in Hammer.
+    Special { command: SpecialCommand, parameters: String },
+}
+
+/// Hammer's built-in commands (`iSpecialCmd`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialCommand {
+    ChangeDir,
+    CopyFile,
+    DeleteFile,
+    RenameFile,
+    CopyFileIfExists,
+    /// A command this crate does not know, kept by its code.
+    Other(i32),
+}
+
+impl SpecialCommand {
+    fn from_code(code: i32) -> Self {
+        match code {
+            0x100 => Self::ChangeDir,
+            0x101 => Self::CopyFile,
+            0x102 => Self::DeleteFile,
+            0x103 => Self::RenameFile,
+            0x104 => Self::CopyFileIfExists,
+            code => Self::Other(code),
+        }
+    }
+
+    fn code(self) -> i32 {
+        match self {
+            Self::ChangeDir => 0x100,
+            Self::CopyFile => 0x101,
+            Self::DeleteFile => 0x102,
+            Self::RenameFile => 0x103,
+            Self::CopyFileIfExists => 0x104,
+            Self::Other(code) => code,
+        }
+    }
+}
+
+/// Hammer macros and the placeholders they become. Hammer's `$path` is the map's directory,
+/// unlike this crate's `$path` alias, which is the full map path.
+const HAMMER_MACROS: &[(&str, &str)] = &[
+    ("file", "$mapName"),
+    ("ext", "$mapExt"),
+    ("path", "$mapDir"),
+    ("bspdir", "$outDir"),
+    ("gamedir", "$gameDir"),
+    ("exedir", "$binDir"),
+];
+
+/// Placeholders and the Hammer macros they are written as.
+const PLACEHOLDER_MACROS: &[(&str, &str)] = &[
+    ("mapNameExt", "$file.$ext"),
+    ("mapName", "$file"),
+    ("mapExt", "$ext"),
+    ("mapDir", "$path"),
+    ("mapPath", "$path\\$file.$ext"),
+    ("bspPath", "$path\\$file.bsp"),
+    ("outDir", "$bspdir"),
+    ("gameDir", "$gamedir"),
+    ("binDir", "$exedir"),
+    ("file", "$file"),
+    ("path", "$path\\$file.$ext"),
+];
+
+/// Hammer macros that expand to a path, which may contain spaces.
+const PATH_MACROS: &[&str] = &["$path", "$bspdir", "$gamedir", "$exedir"];
+
+/// The executables behind Hammer's `$*_exe` macros in the "Run" field.
+const EXECUTABLE_MACROS: &[(&str, &str)] = &[
+    ("$bsp_exe", "vbsp.exe"),
+    ("$vis_exe", "vvis.exe"),
+    ("$light_exe", "vrad.exe"),
+    ("$game_exe", "hl2.exe"),
+];
+
+/// Replaces Hammer macros in `input` with placeholders, e.g. `$path\$file.bsp` becomes
+/// `$mapDir\$mapName.bsp`. Like Hammer, macro names are matched case-insensitively
+/// and the longest match wins. Unknown macros are left as they are.
+pub fn from_hammer_macros(input: &str) -> String {
+    substitute(input, HAMMER_MACROS, true)
+}
+
+/// The inverse of [`from_hammer_macros`]: writes placeholders as Hammer macros.
+/// Placeholders without a Hammer counterpart are left as they are.
+pub fn to_hammer_macros(input: &str) -> String {
+    substitute(input, PLACEHOLDER_MACROS, false)
+}
+
+fn substitute(input: &str, table: &[(&str, &str)], ignore_case: bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let name = &rest[pos + 1..];
+        let found = table
+            .iter()
+            .filter(|(key, _)| {
+                name.get(..key.len()).is_some_and(|prefix| {
+                    if ignore_case { prefix.eq_ignore_ascii_case(key) } else { prefix == *key }
+                })
+            })
+            .max_by_key(|(key, _)| key.len());
+        match found {
+            Some((key, replacement)) => {
+                result.push_str(replacement);
+                rest = &name[key.len()..];
+            }
+            None => {
+                result.push('$');
+                rest = name;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+impl SequenceCommand {
+    /// Creates an enabled step from a "Run" field and Hammer parameters.
+    /// The step becomes a [`SequenceStep::Compiler`] if `run` names a known compiler whose
+    /// arguments parse; otherwise it is a [`SequenceStep::Program`].
+    pub fn new(run: impl Into<String>, parameters: &str) -> Self {
+        let run = run.into();
+        let compiler = parse_compiler(&run, parameters);
+        let source = compiler.as_ref().map(|compiler| (parameters.to_string(), compiler.build_args()));
+        let step = compiler
+            .map(SequenceStep::Compiler)
+            .unwrap_or_else(|| SequenceStep::Program { parameters: parameters.to_string() });
+        Self {
+            enabled: true,
+            run,
+            step,
+            ensure_check: false,
+            ensure_file: String::new(),
+            long_filenames: false,
+            use_process_window: true,
+            no_wait: false,
+            source,
+        }
+    }
+
+    /// Returns the parameters as Hammer stores them, with Hammer macros.
+    ///
+    /// A compiler step whose arguments did not change since it was read returns the parameters
+    /// as they were written. Otherwise they are rebuilt from the arguments; if the original
+    /// parameters used quotes, arguments with a path macro such as `$gamedir` are quoted again.
+    pub fn parameters(&self) -> String {
+        match &self.step {
+            SequenceStep::Compiler(compiler) => {
+                // Base arguments are implied by the compiler, but Hammer needs them spelled out.
+                let args = compiler.build_args();
+                if let Some((parameters, parsed)) = &self.source
+                    && *parsed == args
+                {
+                    return parameters.clone();
+                }
+                let quote_paths = self.source.as_ref().is_some_and(|(parameters, _)| parameters.contains('"'));
+                let mut line = String::new();
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        line.push(' ');
+                    }
+                    let arg = to_hammer_macros(arg);
+                    let has_path = PATH_MACROS.iter().any(|name| arg.contains(name));
+                    // `$` is not special to Windows quoting, so asking for it quotes the argument.
+                    let also_quote: &[char] = if quote_paths && has_path { &['$'] } else { &[] };
+                    command_line::quote_windows(&arg, also_quote, &mut line);
+                }
+                line
+            }
+            SequenceStep::Program { parameters } | SequenceStep::Special { parameters, .. } => parameters.clone(),
+        }
+    }
+
+    /// Converts the step into a pipeline stage, translating Hammer macros.
+    /// Returns `None` for Hammer's built-in commands, which are not processes.
+    ///
+    /// A compiler named through a `$*_exe` macro is located like any other compiler;
+    /// an explicit path in the "Run" field becomes the stage's executable.
+    /// Other programs become a [`Custom`] stage.
+    pub fn to_stage(&self) -> Option<PipelineStage> {
+        let run = from_hammer_macros(&self.run);
+        match &self.step {
+            SequenceStep::Compiler(compiler) => {
+                let stage = PipelineStage::new(compiler.clone());
+                let is_macro = EXECUTABLE_MACROS.iter().any(|(name, _)| self.run.eq_ignore_ascii_case(name));
+                Some(if is_macro { stage } else { stage.with_executable(run) })
+            }
+            SequenceStep::Program { parameters } => {
+                let args = command_line::split_windows(&from_hammer_macros(parameters));
+                Some(PipelineStage::new(Custom::with_program(run, args)))
+            }
+            SequenceStep::Special { .. } => None,
+        }
+    }
+
+    /// Builds the command this step runs, resolving placeholders and Hammer macros with `context`.
+    /// Returns `None` for Hammer's built-in commands, see [`to_stage`](Self::to_stage).
+    pub fn build_command(&self, context: &CompilerContext) -> Option<CommandInfo> {
+        self.to_stage().map(|stage| stage.build_command(context))
+    }
+}
+
+impl CommandSequence {
+    /// Converts every enabled step that runs a process into a pipeline stage, in order.
+    ///
+    /// Hammer's built-in file commands have no stage and are left out. They are returned
+    /// alongside the pipeline, as are steps using `no_wait` or `ensure_check`, which the
+    /// pipeline runs without these options. Disabled steps are neither run nor reported.
+    pub fn to_pipeline(&self) -> (Pipeline, Vec<UnsupportedStep>) {
+        let mut pipeline = Pipeline::new();
+        let mut unsupported = Vec::new();
+        for (index, command) in self.commands.iter().enumerate().filter(|(_, command)| command.enabled) {
+            let mut report = |reason| unsupported.push(UnsupportedStep { index, run: command.run.clone(), reason });
+            if let SequenceStep::Special { command: special, .. } = command.step {
+                report(UnsupportedReason::Special(special));
+            }
+            if command.no_wait {
+                report(UnsupportedReason::NoWait);
+            }
+            if command.ensure_check {
+                report(UnsupportedReason::EnsureCheck { file: command.ensure_file.clone() });
+            }
+            if let Some(stage) = command.to_stage() {
+                pipeline.add_stage(stage);
+            }
+        }
+        (pipeline, unsupported)
+    }
+
+    /// Builds the commands of every enabled step that runs a process, in order.
+    /// The steps are reported as in [`to_pipeline`](Self::to_pipeline).
+    pub fn build_commands(&self, context: &CompilerContext) -> (Vec<CommandInfo>, Vec<UnsupportedStep>) {
+        let (pipeline, unsupported) = self.to_pipeline();
+        (pipeline.stages.iter().map(|stage| stage.build_command(context)).collect(), unsupported)
+    }
+}
+
+/// What a pipeline built from a [`CommandSequence`] cannot do like Hammer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnsupportedReason {
+    /// A built-in file command, which is left out of the pipeline.
+    Special(SpecialCommand),
+    /// Hammer starts the next step right away; the pipeline waits for this one.
+    NoWait,
+    /// Hammer stops the sequence if `file` is missing after the step; the pipeline does not check it.
+    EnsureCheck { file: String },
+}
+
+/// A step of a [`CommandSequence`] that does not translate fully into a pipeline.
+/// A step can be reported for more than one reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedStep {
+    /// The 0-based index of the step in [`CommandSequence::commands`].
+    pub index: usize,
+    /// The "Run" field of the step.
+    pub run: String,
+    pub reason: UnsupportedReason,
+}
+
+impl std::fmt::Display for UnsupportedStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "step {} '{}': ", self.index + 1, self.run)?;
+        match &self.reason {
+            UnsupportedReason::Special(command) => write!(f, "built-in command {:?} is not run", command),
+            UnsupportedReason::NoWait => write!(f, "the next step waits for this one"),
+            UnsupportedReason::EnsureCheck { file } => write!(f, "'{}' is not checked", file),
+        }
+    }
+}
+
+/// Parses `parameters` for the first compiler matching the "Run" field that accepts them.
+/// A compiler is only used if it writes the same arguments back, so nothing is lost.
+fn parse_compiler(run: &str, parameters: &str) -> Option<CompilerEnum> {
+    let executable = EXECUTABLE_MACROS
+        .iter()
+        .find(|(name, _)| run.eq_ignore_ascii_case(name))
+        .map(|(_, executable)| *executable)
+        .unwrap_or_else(|| run.rsplit(['\\', '/']).next().unwrap_or(run));
+    let tokens = command_line::split_windows(&from_hammer_macros(parameters));
+    CompilerEnum::for_executable(executable)
+        .into_iter()
+        .filter_map(|compiler| compiler.parse_command_line(&command_line::join_windows(&tokens)).ok())
+        .find(|compiler| same_arguments(&compiler.build_args(), &tokens))
+}
+
+/// Compares argument lists, treating numbers that only differ in formatting (`2.0`, `2`) as equal.
+fn same_arguments(left: &[String], right: &[String]) -> bool {
+    left.len() == right.len()
+        && left.iter().zip(right).all(|(l, r)| {
+            l == r || matches!((l.parse::<f64>(), r.parse::<f64>()), (Ok(l), Ok(r)) if l == r)
+        })
+}
+
+/// Parses the contents of a `CmdSeq.wc`.
+pub fn parse(data: &[u8]) -> Result<Vec<CommandSequence>, CmdSeqError> {
+    let mut reader = Reader { data };
+    if reader.take(SIGNATURE.len())? != SIGNATURE {
+        return Err(CmdSeqError::InvalidSignature);
+    }
+    let version = f32::from_le_bytes(reader.take_array()?);
+    // Version 0.1 predates the last two fields of a command.
+    let has_window_options = match version {
+        0.1 => false,
+        0.2 => true,
+        version => return Err(CmdSeqError::UnsupportedVersion(version)),
+    };
+
+    let count = reader.u32()?;
+    let mut sequences = Vec::new();
+    for _ in 0..count {
+        let name = reader.string(NAME_FIELD_LEN)?;
+        let command_count = reader.u32()?;
+        let mut commands = Vec::new();
+        for _ in 0..command_count {
+            let enabled = reader.u32()? != 0;
+            let special = reader.u32()? as i32;
+            let run = reader.string(PATH_FIELD_LEN)?;
+            let parameters = reader.string(PATH_FIELD_LEN)?;
+            let long_filenames = reader.u32()? != 0;
+            let ensure_check = reader.u32()? != 0;
+            let ensure_file = reader.string(PATH_FIELD_LEN)?;
+            let (use_process_window, no_wait) = if has_window_options {
+                (reader.u32()? != 0, reader.u32()? != 0)
+            } else {
+                (true, false)
+            };
+
+            let mut command = match special {
+                0 => SequenceCommand::new(run, &parameters),
+                code => SequenceCommand {
+                    step: SequenceStep::Special { command: SpecialCommand::from_code(code), parameters },
+                    ..SequenceCommand::new(run, "")
+                },
+            };
+            command.enabled = enabled;
+            command.ensure_check = ensure_check;
+            command.ensure_file = ensure_file;
+            command.long_filenames = long_filenames;
+            command.use_process_window = use_process_window;
+            command.no_wait = no_wait;
+            commands.push(command);
+        }
+        sequences.push(CommandSequence { name, commands });
+    }
+    Ok(sequences)
+}
+
+/// Parses the KeyValues text format, see the [module documentation](self).
+pub fn parse_text(input: &str) -> Result<Vec<CommandSequence>, CmdSeqError> {
+    let document = keyvalues::parse_unescaped(input).map_err(CmdSeqError::Text)?;
+    let root = document.iter().find_map(|(_, value)| value.as_block()).ok_or(CmdSeqError::InvalidSignature)?;
+
+    let sequences = root.iter().filter_map(|(name, value)| Some((name, value.as_block()?)));
+    Ok(sequences
+        .map(|(name, block)| CommandSequence {
+            name: name.to_string(),
+            commands: block.iter().filter_map(|(_, value)| value.as_block()).map(command_from_block).collect(),
+        })
+        .collect())
+}
+
+fn command_from_block(block: &Block) -> SequenceCommand {
+    let text = |key: &str| block.get_str(key).unwrap_or_default().to_string();
+    let number = |key: &str| block.get_str(key).and_then(|value| value.trim().parse::<i32>().ok());
+    let flag = |key: &str, default: bool| number(key).map_or(default, |value| value != 0);
+
+    let mut command = match number("specialcmd").unwrap_or(0) {
+        0 => SequenceCommand::new(text("run"), &text("params")),
+        code => SequenceCommand {
+            step: SequenceStep::Special { command: SpecialCommand::from_code(code), parameters: text("params") },
+            ..SequenceCommand::new(text("run"), "")
+        },
+    };
+    command.enabled = flag("enable", command.enabled);
+    command.ensure_check = flag("ensurecheck", command.ensure_check);
+    command.ensure_file = text("ensurefn");
+    command.long_filenames = flag("longfilenames", command.long_filenames);
+    command.use_process_window = flag("useprocesswnd", command.use_process_window);
+    command.no_wait = flag("nowait", command.no_wait);
+    command
+}
+
+/// Writes sequences in the KeyValues text format.
+pub fn to_text(sequences: &[CommandSequence]) -> Result<String, CmdSeqError> {
+    let flag = |value: bool| Value::Text(u32::from(value).to_string());
+    let text = |field: &'static str, value: String| {
+        if value.contains('"') {
+            return Err(CmdSeqError::QuoteInText { field, value });
+        }
+        Ok(Value::Text(value))
+    };
+    let sequences = sequences.iter().map(|sequence| {
+        let commands = sequence.commands.iter().enumerate().map(|(number, command)| {
+            let special = match &command.step {
+                SequenceStep::Special { command, .. } => command.code(),
+                _ => 0,
+            };
+            let entries = vec![
+                ("enable".to_string(), flag(command.enabled)),
+                ("specialcmd".to_string(), Value::Text(special.to_string())),
+                ("run".to_string(), text("run", command.run.clone())?),
+                ("params".to_string(), text("parameters", command.parameters())?),
+                ("ensurecheck".to_string(), flag(command.ensure_check)),
+                ("ensurefn".to_string(), text("ensure file", command.ensure_file.clone())?),
+                ("useprocesswnd".to_string(), flag(command.use_process_window)),
+                ("nowait".to_string(), flag(command.no_wait)),
+            ];
+            Ok(((number + 1).to_string(), Value::Block(Block { entries })))
+        });
+        let commands = commands.collect::<Result<_, _>>()?;
+        Ok((sequence.name.clone(), Value::Block(Block { entries: commands })))
+    });
+    let root = Block { entries: sequences.collect::<Result<_, _>>()? };
+    let document = Block { entries: vec![("Command Sequences".to_string(), Value::Block(root))] };
+    Ok(keyvalues::serialize_unescaped(&document))
+}
+
+/// Reads and parses a run configuration file in either format.
+/// Files starting with [`SIGNATURE`] are read as binary, anything else as text.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<CommandSequence>, CmdSeqError> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).map_err(|source| CmdSeqError::Io { path: path.to_path_buf(), source })?;
+    if data.starts_with(SIGNATURE) {
+        parse(&data)
+    } else {
+        parse_text(&String::from_utf8_lossy(&data))
+    }
+}
+
+/// Serializes sequences in the format Hammer writes (version 0.2).
+pub fn to_bytes(sequences: &[CommandSequence]) -> Result<Vec<u8>, CmdSeqError> {
+    let mut data = SIGNATURE.to_vec();
+    data.extend_from_slice(&VERSION.to_le_bytes());
+    data.extend_from_slice(&(sequences.len() as u32).to_le_bytes());
+    for sequence in sequences {
+        write_string(&mut data, "sequence name", &sequence.name, NAME_FIELD_LEN)?;
+        data.extend_from_slice(&(sequence.commands.len() as u32).to_le_bytes());
+        for command in &sequence.commands {
+            let special = match &command.step {
+                SequenceStep::Special { command, .. } => command.code(),
+                _ => 0,
+            };
+            data.extend_from_slice(&u32::from(command.enabled).to_le_bytes());
+            data.extend_from_slice(&special.to_le_bytes());
+            write_string(&mut data, "run", &command.run, PATH_FIELD_LEN)?;
+            write_string(&mut data, "parameters", &command.parameters(), PATH_FIELD_LEN)?;
+            data.extend_from_slice(&u32::from(command.long_filenames).to_le_bytes());
+            data.extend_from_slice(&u32::from(command.ensure_check).to_le_bytes());
+            write_string(&mut data, "ensure file", &command.ensure_file, PATH_FIELD_LEN)?;
+            data.extend_from_slice(&u32::from(command.use_process_window).to_le_bytes());
+            data.extend_from_slice(&u32::from(command.no_wait).to_le_bytes());
+        }
+    }
+    Ok(data)
+}
+
+/// Serializes sequences in the binary format and writes them to `path`.
+pub fn save(path: impl AsRef<Path>, sequences: &[CommandSequence]) -> Result<(), CmdSeqError> {
+    let path = path.as_ref();
+    let data = to_bytes(sequences)?;
+    std::fs::write(path, data).map_err(|source| CmdSeqError::Io { path: path.to_path_buf(), source })
+}
+
+/// Writes `value` into a zero-padded field of `len` bytes, keeping room for the terminator.
+fn write_string(data: &mut Vec<u8>, field: &'static str, value: &str, len: usize) -> Result<(), CmdSeqError> {
+    let bytes = value.as_bytes();
+    if bytes.len() >= len {
+        return Err(CmdSeqError::FieldTooLong { field, value: value.to_string(), max: len - 1 });
+    }
+    data.extend_from_slice(bytes);
+    data.resize(data.len() + len - bytes.len(), 0);
+    Ok(())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CmdSeqError> {
+        if self.data.len() < len {
+            return Err(CmdSeqError::UnexpectedEof);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], CmdSeqError> {
+        Ok(self.take(N)?.try_into().expect("slice has the requested length"))
+    }
+
+    fn u32(&mut self) -> Result<u32, CmdSeqError> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    /// Reads a zero-terminated string from a fixed-size field. Hammer writes the ANSI code page;
+    /// anything that is not valid UTF-8 is read as Latin-1.
+    fn string(&mut self, len: usize) -> Result<String, CmdSeqError> {
+        let field = self.take(len)?;
+        let bytes = &field[..field.iter().position(|&b| b == 0).unwrap_or(len)];
+        Ok(match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => bytes.iter().map(|&b| char::from(b)).collect(),
+        })
+    }
+}
@@ -0,0 +1,342 @@
+//! Splitting command-line strings into argument lists, and quoting them for a shell.
+
+/// A shell that a command line or script is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShellKind {
+    /// `cmd.exe` and `.bat` files.
+    Cmd,
+    /// PowerShell 7.3 or newer. Older versions drop empty arguments and mangle embedded `"`.
+    PowerShell,
+    /// A POSIX `sh` compatible shell.
+    Posix,
+}
+
+impl ShellKind {
+    /// The usual file extension of scripts for this shell, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Cmd => "bat",
+            Self::PowerShell => "ps1",
+            Self::Posix => "sh",
+        }
+    }
+}
+
+/// Splits a command line into arguments the way `CommandLineToArgvW` and the MSVC runtime do.
+///
+/// * Spaces and tabs separate arguments, unless they are inside double quotes.
+/// * `2n` backslashes followed by `"` produce `n` backslashes and toggle quoting.
+/// * `2n + 1` backslashes followed by `"` produce `n` backslashes and a literal `"`.
+/// * Backslashes not followed by `"` are literal, so `C:\maps\` needs no escaping.
+/// * Inside quotes, `""` produces a literal `"`.
+///
+/// The input is expected to contain only the arguments, without the program name.
+pub fn split_windows(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Distinguishes `""` (an empty argument) from no argument at all.
+    let mut has_token = false;
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' if !in_quotes => {
+                if has_token {
+                    args.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            '\\' => {
+                let mut backslashes = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    backslashes += 1;
+                }
+                if chars.peek() == Some(&'"') {
+                    current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                    if backslashes % 2 == 1 {
+                        current.push('"');
+                        chars.next();
+                    }
+                } else {
+                    current.extend(std::iter::repeat_n('\\', backslashes));
+                }
+                has_token = true;
+            }
+            '"' => {
+                if in_quotes && chars.next_if_eq(&'"').is_some() {
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                has_token = true;
+            }
+            _ => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+
+    if has_token {
+        args.push(current);
+    }
+    args
+}
+
+/// Splits a complete Windows command line, program name first, the way `CommandLineToArgvW` does.
+///
+/// The program name has no escapes: it ends at the next `"` if it starts with one, and at
+/// the first space or tab otherwise. The rest of the line is split with [`split_windows`].
+pub fn split_windows_command(line: &str) -> Vec<String> {
+    let line = line.trim_start_matches([' ', '\t']);
+    let (program, rest) = match line.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+        None => line.split_once([' ', '\t']).unwrap_or((line, "")),
+    };
+    let mut args = vec![program.to_string()];
+    args.extend(split_windows(rest));
+    args
+}
+
+/// Splits a line the way a POSIX shell does, without any expansions.
+///
+/// * Spaces, tabs and newlines separate arguments.
+/// * `'...'` is taken literally.
+/// * Inside `"..."`, a backslash only escapes `$`, `` ` ``, `"`, `\` and newlines.
+/// * Outside quotes, a backslash escapes any character, and a backslash-newline joins lines.
+///
+/// `$VAR`, `*` and other shell syntax are kept as they are.
+pub fn split_posix(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut has_token = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => {
+                if has_token {
+                    args.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            '\'' => {
+                current.extend(chars.by_ref().take_while(|&c| c != '\''));
+                has_token = true;
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next_if(|next| matches!(next, '$' | '`' | '"' | '\\' | '\n')) {
+                            Some('\n') => {}
+                            Some(escaped) => current.push(escaped),
+                            None => current.push('\\'),
+                        },
+                        _ => current.push(c),
+                    }
+                }
+                has_token = true;
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                escaped => {
+                    current.push(escaped.unwrap_or('\\'));
+                    has_token = true;
+                }
+            },
+            _ => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+
+    if has_token {
+        args.push(current);
+    }
+    args
+}
+
+/// Splits a complete command line for `shell`, program first. This is the inverse of
+/// [`CommandInfo::to_command_line`](crate::CommandInfo::to_command_line).
+///
+/// * [`ShellKind::Cmd`]: `^` escapes outside quotes are removed, then the line is split
+///   with [`split_windows_command`].
+/// * [`ShellKind::PowerShell`]: an optional leading `&`, then single-quoted strings and bare words.
+///   Other PowerShell syntax is not interpreted.
+/// * [`ShellKind::Posix`]: split with [`split_posix`].
+pub fn split_command_line(line: &str, shell: ShellKind) -> Vec<String> {
+    match shell {
+        ShellKind::Cmd => split_windows_command(&unescape_cmd(line)),
+        ShellKind::PowerShell => split_powershell(line),
+        ShellKind::Posix => split_posix(line),
+    }
+}
+
+fn split_powershell(line: &str) -> Vec<String> {
+    let line = line.trim_start();
+    let line = line.strip_prefix('&').filter(|rest| rest.starts_with(char::is_whitespace)).unwrap_or(line);
+    let is_quote = |c: &char| matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}');
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut has_token = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            if has_token {
+                args.push(std::mem::take(&mut current));
+                has_token = false;
+            }
+        } else if is_quote(&c) {
+            // Two quote characters in a row are one literal quote.
+            while let Some(c) = chars.next() {
+                if is_quote(&c) && chars.next_if(is_quote).is_none() {
+                    break;
+                }
+                current.push(c);
+            }
+            has_token = true;
+        } else {
+            current.push(c);
+            has_token = true;
+        }
+    }
+    if has_token {
+        args.push(current);
+    }
+    args
+}
+
+/// Joins arguments into a command line that [`split_windows`] splits back into exactly `args`.
+///
+/// Arguments that are empty or contain spaces, tabs or `"` are quoted. Inside quotes,
+/// backslashes are only doubled where they precede a `"`, so paths stay readable.
+pub fn join_windows<S: AsRef<str>>(args: &[S]) -> String {
+    let mut line = String::new();
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            line.push(' ');
+        }
+        quote_windows(arg.as_ref(), &[], &mut line);
+    }
+    line
+}
+
+/// Quotes a single argument so that `shell` passes it to the program unchanged.
+///
+/// * [`ShellKind::Cmd`]: Windows quoting as in [`join_windows`]. Arguments containing
+///   `& | < > ^ ( )` are quoted as well, and any of these left outside quotes are escaped with `^`.
+///   `%` is left as it is; batch files need it doubled, which [`crate::script`] does.
+/// * [`ShellKind::PowerShell`]: single quotes, with `'` doubled.
+/// * [`ShellKind::Posix`]: single quotes, with `'` written as `'\''`.
+///
+/// Arguments that need no quoting are returned as they are.
+pub fn quote(arg: &str, shell: ShellKind) -> String {
+    match shell {
+        ShellKind::Cmd => {
+            let mut quoted = String::new();
+            quote_windows(arg, CMD_SPECIAL, &mut quoted);
+            escape_cmd(&quoted)
+        }
+        ShellKind::PowerShell => {
+            // `-a.b` and `-a:b` are split in two by PowerShell unless quoted.
+            let bare = !arg.is_empty()
+                && arg.chars().all(|c| c.is_ascii_alphanumeric() || "_-./\\:".contains(c))
+                && !(arg.starts_with('-') && arg.contains(['.', ':']));
+            if bare {
+                return arg.to_string();
+            }
+            let mut quoted = String::from("'");
+            for c in arg.chars() {
+                // PowerShell also accepts typographic single quotes as delimiters.
+                if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+                    quoted.push(c);
+                }
+                quoted.push(c);
+            }
+            quoted.push('\'');
+            quoted
+        }
+        ShellKind::Posix => {
+            if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c)) {
+                return arg.to_string();
+            }
+            format!("'{}'", arg.replace('\'', r"'\''"))
+        }
+    }
+}
+
+/// Joins arguments with [`quote`] into a line for `shell`.
+pub fn join<S: AsRef<str>>(args: &[S], shell: ShellKind) -> String {
+    args.iter().map(|arg| quote(arg.as_ref(), shell)).collect::<Vec<_>>().join(" ")
+}
+
+/// Characters that `cmd.exe` interprets outside of double quotes.
+pub(crate) const CMD_SPECIAL: &[char] = &['&', '|', '<', '>', '^', '(', ')'];
+
+/// Escapes [`CMD_SPECIAL`] characters that `cmd.exe` sees outside of quotes. `cmd.exe` toggles
+/// quoting on every `"`, including the `\"` escapes of the Windows rules.
+pub(crate) fn escape_cmd(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    let mut in_quotes = false;
+    for c in line.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && CMD_SPECIAL.contains(&c) {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Removes the `^` escapes that [`escape_cmd`] adds.
+fn unescape_cmd(line: &str) -> String {
+    let mut unescaped = String::with_capacity(line.len());
+    let mut in_quotes = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '^' if !in_quotes => {
+                unescaped.extend(chars.next());
+                continue;
+            }
+            _ => {}
+        }
+        unescaped.push(c);
+    }
+    unescaped
+}
+
+pub(crate) fn quote_windows(arg: &str, also_quote: &[char], out: &mut String) {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) && !arg.contains(also_quote) {
+        out.push_str(arg);
+        return;
+    }
+
+    out.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                out.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                out.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                out.extend(std::iter::repeat_n('\\', backslashes));
+                out.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Trailing backslashes precede the closing quote.
+    out.extend(std::iter::repeat_n('\\', backslashes * 2));
+    out.push('"');
+}
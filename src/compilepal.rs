@@ -0,0 +1,210 @@
+//! Importing presets from CompilePal.
+//!
+//! A CompilePal preset is a folder holding one CSV file per compiler (`vbsp.csv`, `vrad.csv`,
+//! ...), with one parameter per line: the switch, a comma, and the value if it takes one.
+//!
+//! ```text
+//! -final,
+//! -StaticPropLighting,
+//! -bounce,8
+//! ```
+//!
+//! Lines are parsed with the generated `TryFrom<&str>` parsers. Lines that cannot be mapped
+//! are collected in [`CompilePalPreset::report`] instead of failing the whole import.
+
+use crate::bspzip::Bspzip;
+use crate::vbsp::Vbsp;
+use crate::vrad::Vrad;
+use crate::vvis::Vvis;
+use crate::{Compiler, ParseArgError, Pipeline, PipelineStage};
+use std::path::{Path, PathBuf};
+
+/// Errors that stop a CompilePal import. Problems with single lines are reported
+/// in [`CompilePalPreset::report`] instead.
+#[derive(Debug)]
+pub enum CompilePalError {
+    /// The preset folder or one of its files could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl std::fmt::Display for CompilePalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read '{}': {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for CompilePalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Why a line of a preset could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnmappedReason {
+    /// The file belongs to a CompilePal step this crate has no compiler for (e.g. `cubemaps.csv`).
+    UnknownCompiler,
+    /// The compiler's parser rejected the argument.
+    Parse(ParseArgError),
+}
+
+/// A line of a preset that could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmappedLine {
+    /// The CSV file the line is in.
+    pub file: PathBuf,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The line as written.
+    pub text: String,
+    pub reason: UnmappedReason,
+}
+
+impl std::fmt::Display for UnmappedLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: '{}': ", self.file.display(), self.line, self.text)?;
+        match &self.reason {
+            UnmappedReason::UnknownCompiler => write!(f, "no compiler for this file"),
+            UnmappedReason::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// The compilers configured by a CompilePal preset. A compiler is `None` if the preset has no file for it.
+///
+/// Each compiler starts from its `Default` arguments (`-game` and the map), which CompilePal adds
+/// on its own, followed by the arguments of the preset.
+#[derive(Debug, Clone, Default)]
+pub struct CompilePalPreset {
+    /// The name of the preset folder.
+    pub name: String,
+    pub vbsp: Option<Vbsp>,
+    pub vvis: Option<Vvis>,
+    pub vrad: Option<Vrad>,
+    pub bspzip: Option<Bspzip>,
+    /// Every line that could not be mapped, in file and line order.
+    pub report: Vec<UnmappedLine>,
+}
+
+impl CompilePalPreset {
+    /// Reads the preset folder `dir`. Files are read in name order.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, CompilePalError> {
+        let dir = dir.as_ref();
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| CompilePalError::Io { path, source }
+        };
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_error(dir))? {
+            let path = entry.map_err(io_error(dir))?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        let mut preset = Self {
+            name: dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+            ..Self::default()
+        };
+        for path in files {
+            let content = std::fs::read_to_string(&path).map_err(io_error(&path))?;
+            preset.import_file(&path, &content);
+        }
+        Ok(preset)
+    }
+
+    /// Imports the contents of one CSV file, picking the compiler from the file name.
+    pub fn import_file(&mut self, path: &Path, content: &str) {
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let report = &mut self.report;
+        match stem.as_str() {
+            "vbsp" => import_lines::<Vbsp>(self.vbsp.get_or_insert_with(Vbsp::default), path, content, report),
+            "vvis" => import_lines::<Vvis>(self.vvis.get_or_insert_with(Vvis::default), path, content, report),
+            "vrad" => import_lines::<Vrad>(self.vrad.get_or_insert_with(Vrad::default), path, content, report),
+            "bspzip" => import_lines::<Bspzip>(self.bspzip.get_or_insert_with(Bspzip::default), path, content, report),
+            _ => {
+                for (number, text) in preset_lines(content) {
+                    report.push(UnmappedLine {
+                        file: path.to_path_buf(),
+                        line: number,
+                        text: text.to_string(),
+                        reason: UnmappedReason::UnknownCompiler,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Builds a pipeline running the imported compilers in compile order.
+    pub fn to_pipeline(&self) -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        if let Some(vbsp) = &self.vbsp {
+            pipeline.add_stage(PipelineStage::new(vbsp.clone()));
+        }
+        if let Some(vvis) = &self.vvis {
+            pipeline.add_stage(PipelineStage::new(vvis.clone()));
+        }
+        if let Some(vrad) = &self.vrad {
+            pipeline.add_stage(PipelineStage::new(vrad.clone()));
+        }
+        if let Some(bspzip) = &self.bspzip {
+            pipeline.add_stage(PipelineStage::new(bspzip.clone()));
+        }
+        pipeline
+    }
+}
+
+/// Returns the non-empty lines of a preset file with their 1-based numbers.
+fn preset_lines(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn import_lines<C>(compiler: &mut C, path: &Path, content: &str, report: &mut Vec<UnmappedLine>)
+where
+    C: Compiler,
+    for<'a> C::Arg: TryFrom<&'a str, Error = ParseArgError>,
+{
+    for (number, text) in preset_lines(content) {
+        match parse_line(compiler, text) {
+            Ok(arg) => compiler.add_arg(arg),
+            Err(err) => report.push(UnmappedLine {
+                file: path.to_path_buf(),
+                line: number,
+                text: text.to_string(),
+                reason: UnmappedReason::Parse(err),
+            }),
+        }
+    }
+}
+
+/// Parses `-switch,value` into an argument. The compilers read switches case-insensitively,
+/// so CompilePal's `-textureshadows` is matched to `-TextureShadows`.
+fn parse_line<C>(compiler: &C, text: &str) -> Result<C::Arg, ParseArgError>
+where
+    C: Compiler,
+    for<'a> C::Arg: TryFrom<&'a str, Error = ParseArgError>,
+{
+    let (switch, value) = text.split_once(',').unwrap_or((text, ""));
+    let (switch, value) = (switch.trim(), value.trim());
+    let switch = compiler
+        .parameters()
+        .iter()
+        .find(|spec| !spec.argument.is_empty() && spec.argument.eq_ignore_ascii_case(switch))
+        .map_or(switch, |spec| spec.argument);
+
+    let arg = if value.is_empty() { switch.to_string() } else { format!("{} {}", switch, value) };
+    C::Arg::try_from(arg.as_str())
+}
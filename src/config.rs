@@ -0,0 +1,320 @@
+//! The TOML schema of `compiler_configs/*.toml`.
+//!
+//! This file is shared by `build.rs` (via `#[path]`), which generates the built-in compilers,
+//! and by the `dynamic` feature, which loads the same schema at runtime.
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CompilerConfig {
+    pub name: String,
+    pub description: String,
+    pub working_dir: String,
+    /// Arguments that are always passed to the compiler, before any user-selected ones.
+    /// Compilers that expect the map last (VBSP, VVIS, VRAD) declare it as a default parameter instead.
+    #[serde(default)]
+    pub base_arguments: String,
+    /// Executable names and search directories. Defaults to `<name>.exe` in `$binDir`.
+    pub executable: Option<ExecutableConfig>,
+    #[serde(default)]
+    pub parameters: Vec<ParameterConfig>,
+    /// Changes to the default arguments for specific games, applied in order.
+    #[serde(default)]
+    pub game_defaults: Vec<GameDefaultsConfig>,
+    /// Named argument sets (e.g. "fast", "final") applied on top of the defaults.
+    #[serde(default)]
+    pub presets: Vec<PresetConfig>,
+}
+
+/// A `[[game_defaults]]` entry: arguments added to or removed from the defaults for some games.
+#[derive(Debug, Deserialize, Clone)]
+pub struct GameDefaultsConfig {
+    pub games: Vec<GameRef>,
+    /// Arguments to add. An existing argument with the same switch is replaced.
+    #[serde(default)]
+    pub add: Vec<ArgumentRef>,
+    /// Switches of default arguments to drop.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// A `[[presets]]` entry: a named set of changes to the current arguments.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PresetConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Arguments to add. An existing argument with the same switch is replaced.
+    #[serde(default)]
+    pub add: Vec<ArgumentRef>,
+    /// Switches to drop, e.g. those added by other presets.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// A reference to a parameter by its `argument`, optionally with a value.
+/// Without a value, the parameter's `default_value` is used.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ArgumentRef {
+    Switch(String),
+    WithValue { argument: String, value: String },
+}
+
+impl ArgumentRef {
+    pub fn argument(&self) -> &str {
+        match self {
+            Self::Switch(argument) | Self::WithValue { argument, .. } => argument,
+        }
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::Switch(_) => None,
+            Self::WithValue { value, .. } => Some(value),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExecutableConfig {
+    #[serde(default)]
+    pub windows: Vec<String>,
+    #[serde(default)]
+    pub linux: Vec<String>,
+    #[serde(default)]
+    pub macos: Vec<String>,
+    #[serde(default = "ExecutableConfig::default_search_dirs")]
+    pub search_dirs: Vec<String>,
+}
+
+impl ExecutableConfig {
+    fn default_search_dirs() -> Vec<String> {
+        vec!["$binDir".to_string()]
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ParameterConfig {
+    pub name: String,
+    pub description: String,
+    pub argument: String,
+    pub value_type: ValueType,
+    pub default_value: Option<String>,
+    #[serde(default)]
+    pub is_default: bool,
+    pub constraints: Option<ConstraintsConfig>,
+    /// Arguments (by their `argument` string) that must also be selected.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Arguments (by their `argument` string) that must not be selected together with this one.
+    #[serde(default)]
+    pub conflicts_with: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConstraintsConfig {
+    pub compatible_games: Option<Vec<GameRef>>,
+    pub incompatible_games: Option<Vec<GameRef>>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+}
+
+/// A game in a constraint list: either a raw App ID or a name from `games.toml` (e.g. `"tf2"`).
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum GameRef {
+    AppId(u32),
+    Name(String),
+}
+
+/// Resolves game names to App IDs; returns the first name that `lookup` does not know on failure.
+pub fn resolve_games(games: &[GameRef], lookup: &dyn Fn(&str) -> Option<u32>) -> Result<Vec<u32>, String> {
+    games
+        .iter()
+        .map(|game| match game {
+            GameRef::AppId(app_id) => Ok(*app_id),
+            GameRef::Name(name) => lookup(name).ok_or_else(|| name.clone()),
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    Flag,
+    Float,
+    Integer,
+    String,
+    Path,
+}
+
+impl ParameterConfig {
+    /// Checks that `value` parses as this parameter's type and lies within its bounds.
+    ///
+    /// Bounds are compared in the parameter's own type, like the generated parsers do,
+    /// so `0.1` is within `max_value = 0.1`.
+    pub fn check_value(&self, value: &str) -> Result<(), String> {
+        let (min, max) = self.value_bounds().unwrap_or_default();
+        let in_range = match self.value_type {
+            ValueType::Float => value.parse::<f32>().ok().map(|number| {
+                min.is_none_or(|min| number >= min as f32) && max.is_none_or(|max| number <= max as f32)
+            }),
+            ValueType::Integer => value.parse::<i64>().ok().map(|number| {
+                min.is_none_or(|min| number >= min as i64) && max.is_none_or(|max| number <= max as i64)
+            }),
+            _ => return Ok(()),
+        };
+        match in_range {
+            None => Err(format!("invalid value '{}' for '{}'", value, self.name)),
+            Some(false) => Err(format!("value {} for '{}' is outside of the declared range", value, self.name)),
+            Some(true) => Ok(()),
+        }
+    }
+
+    /// Returns the `(min, max)` bounds of a numeric parameter, if any are declared.
+    pub fn value_bounds(&self) -> Option<(Option<f64>, Option<f64>)> {
+        let constraints = self.constraints.as_ref()?;
+        if constraints.min_value.is_none() && constraints.max_value.is_none() {
+            return None;
+        }
+        Some((constraints.min_value, constraints.max_value))
+    }
+}
+
+impl CompilerConfig {
+    /// Resolves an argument reference to the index of its parameter and the value to parse.
+    pub fn resolve_argument(&self, reference: &ArgumentRef) -> Result<(usize, Option<String>), String> {
+        let index = self
+            .parameters
+            .iter()
+            .position(|p| p.argument == reference.argument())
+            .ok_or_else(|| format!("'{}' does not match the argument of any parameter in this config", reference.argument()))?;
+        let param = &self.parameters[index];
+
+        if param.value_type == ValueType::Flag {
+            return match reference.value() {
+                Some(_) => Err(format!("'{}' is a flag and does not take a value", param.name)),
+                None => Ok((index, None)),
+            };
+        }
+        let value = reference
+            .value()
+            .or(param.default_value.as_deref())
+            .ok_or_else(|| format!("'{}' needs a value, as its parameter has no default_value", param.name))?;
+        param.check_value(value)?;
+        Ok((index, Some(value.to_string())))
+    }
+
+    /// Returns the `[executable]` table, falling back to `<name>.exe` in `$binDir`.
+    pub fn executable_or_default(&self) -> ExecutableConfig {
+        self.executable.clone().unwrap_or_else(|| ExecutableConfig {
+            windows: vec![format!("{}.exe", self.name.to_lowercase())],
+            linux: Vec::new(),
+            macos: Vec::new(),
+            search_dirs: ExecutableConfig::default_search_dirs(),
+        })
+    }
+
+    /// Checks constraints that serde cannot express. `lookup_game` resolves game names
+    /// in `compatible_games`/`incompatible_games` to App IDs.
+    /// On failure, returns the offending parameter name and a reason.
+    pub fn validate(&self, lookup_game: &dyn Fn(&str) -> Option<u32>) -> Result<(), (String, String)> {
+        let executable = self.executable_or_default();
+        if executable.windows.is_empty() && executable.linux.is_empty() && executable.macos.is_empty() {
+            return Err(("[executable]".to_string(), "at least one executable name is required".to_string()));
+        }
+        if executable.search_dirs.is_empty() {
+            return Err(("[executable]".to_string(), "search_dirs must not be empty".to_string()));
+        }
+
+        for param in &self.parameters {
+            let fail = |reason: String| Err((param.name.clone(), reason));
+
+            if param.is_default && param.value_type != ValueType::Flag && param.default_value.is_none() {
+                return fail("parameters with is_default = true need a default_value".to_string());
+            }
+            if let Some(default) = &param.default_value {
+                let parses = match param.value_type {
+                    ValueType::Float => default.parse::<f32>().is_ok(),
+                    ValueType::Integer => default.parse::<i64>().is_ok(),
+                    _ => true,
+                };
+                if !parses {
+                    return fail(format!("invalid default_value '{}'", default));
+                }
+            }
+
+            if let Some(constraints) = &param.constraints {
+                for games in [&constraints.compatible_games, &constraints.incompatible_games].into_iter().flatten() {
+                    if let Err(name) = resolve_games(games, lookup_game) {
+                        return fail(format!("unknown game '{}'", name));
+                    }
+                }
+            }
+
+            for reference in param.requires.iter().chain(&param.conflicts_with) {
+                if reference == &param.argument {
+                    return fail(format!("'{}' cannot require or conflict with itself", reference));
+                }
+                if !self.parameters.iter().any(|p| &p.argument == reference) {
+                    return fail(format!("'{}' does not match the argument of any parameter in this config", reference));
+                }
+            }
+
+            let Some((min, max)) = param.value_bounds() else { continue };
+
+            if !matches!(param.value_type, ValueType::Float | ValueType::Integer) {
+                return fail("min_value/max_value are only supported for float and integer parameters".to_string());
+            }
+            if param.value_type == ValueType::Integer
+                && [min, max].into_iter().flatten().any(|bound| bound.fract() != 0.0)
+            {
+                return fail("bounds of an integer parameter must be whole numbers".to_string());
+            }
+            if let (Some(min), Some(max)) = (min, max) && min > max {
+                return fail(format!("min_value ({}) is greater than max_value ({})", min, max));
+            }
+            if let Some(default) = param.default_value.as_ref().and_then(|d| d.parse::<f64>().ok())
+                && (min.is_some_and(|min| default < min) || max.is_some_and(|max| default > max))
+            {
+                return fail(format!("default_value {} is outside of the declared range", default));
+            }
+        }
+
+        for (number, set) in self.game_defaults.iter().enumerate() {
+            let fail = |reason: String| Err((format!("[[game_defaults]] #{}", number + 1), reason));
+            if set.games.is_empty() {
+                return fail("games must not be empty".to_string());
+            }
+            if let Err(name) = resolve_games(&set.games, lookup_game) {
+                return fail(format!("unknown game '{}'", name));
+            }
+            if let Err(reason) = self.check_changes(&set.add, &set.remove) {
+                return fail(reason);
+            }
+        }
+
+        for (number, preset) in self.presets.iter().enumerate() {
+            let fail = |reason: String| Err((format!("[[presets]] #{}", number + 1), reason));
+            if preset.name.trim().is_empty() {
+                return fail("name must not be empty".to_string());
+            }
+            if self.presets[..number].iter().any(|p| p.name.eq_ignore_ascii_case(&preset.name)) {
+                return fail(format!("duplicate preset name '{}'", preset.name));
+            }
+            if let Err(reason) = self.check_changes(&preset.add, &preset.remove) {
+                return fail(reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the `add`/`remove` lists of a `[[game_defaults]]` or `[[presets]]` entry.
+    fn check_changes(&self, add: &[ArgumentRef], remove: &[String]) -> Result<(), String> {
+        if let Some(unknown) = remove.iter().find(|r| !self.parameters.iter().any(|p| &p.argument == *r)) {
+            return Err(format!("'{}' does not match the argument of any parameter in this config", unknown));
+        }
+        add.iter().try_for_each(|reference| self.resolve_argument(reference).map(drop))
+    }
+}
@@ -0,0 +1,155 @@
+//! A stage for programs that have no compiler config, e.g. a copy script in a Hammer sequence.
+
+use crate::{Compiler, CompilerArg, CompilerContext, ExecutableSpec, ParameterSpec, ParseArgError, PresetSpec, ValueRange, ValueType};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Runs an arbitrary program with a free-form argument list.
+///
+/// The program and arguments may contain placeholders; they are resolved like those of any
+/// other compiler. Unlike the generated compilers, nothing is validated.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct Custom {
+    /// The program to run, e.g. `$binDir/../hl2.exe` or `/usr/bin/cp`.
+    pub program: String,
+    pub selected_args: Vec<CustomArg>,
+}
+
+impl Custom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a stage running `program` with `args`.
+    pub fn with_program(program: impl Into<String>, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            program: program.into(),
+            selected_args: args.into_iter().map(|arg| CustomArg(arg.into())).collect(),
+        }
+    }
+}
+
+impl Compiler for Custom {
+    type Arg = CustomArg;
+
+    fn name(&self) -> &'static str {
+        "Custom Command"
+    }
+
+    fn description(&self) -> &'static str {
+        "Runs a program without a compiler config."
+    }
+
+    fn working_dir_template(&self) -> &'static str {
+        "$mapDir"
+    }
+
+    fn base_arguments(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn executable(&self) -> ExecutableSpec {
+        ExecutableSpec { windows: &[], linux: &[], macos: &[], search_dirs: &[] }
+    }
+
+    fn parameters(&self) -> &'static [ParameterSpec] {
+        &[]
+    }
+
+    fn parse_parameter(&self, index: usize, _value: Option<&str>) -> Result<Self::Arg, ParseArgError> {
+        Err(ParseArgError::UnknownArgument(format!("#{}", index)))
+    }
+
+    fn presets(&self) -> &'static [PresetSpec] {
+        &[]
+    }
+
+    fn apply_preset(&mut self, _name: &str) -> bool {
+        false
+    }
+
+    fn get_args(&self) -> &[Self::Arg] {
+        &self.selected_args
+    }
+
+    fn add_arg(&mut self, arg: Self::Arg) {
+        self.selected_args.push(arg);
+    }
+
+    fn clear_args(&mut self) {
+        self.selected_args.clear();
+    }
+
+    /// Returns `program` with its placeholders resolved; there is nothing to search for.
+    fn resolve_executable(&self, context: &CompilerContext) -> PathBuf {
+        PathBuf::from(context.replace_native(&self.program))
+    }
+
+    /// Every token is kept as a positional argument.
+    fn extend_from_args<S: AsRef<str>>(&mut self, tokens: &[S]) -> Result<(), ParseArgError> {
+        self.selected_args.extend(tokens.iter().map(|token| CustomArg(token.as_ref().to_string())));
+        Ok(())
+    }
+}
+
+/// A single argument of a [`Custom`] stage, passed through verbatim.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomArg(pub String);
+
+impl CompilerArg for CustomArg {
+    fn name(&self) -> &'static str {
+        "Argument"
+    }
+
+    fn description(&self) -> &'static str {
+        ""
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::String
+    }
+
+    fn get_default_value(&self) -> Option<Self> {
+        None
+    }
+
+    fn argument(&self) -> &'static str {
+        ""
+    }
+
+    fn as_arg(&self) -> (&'static str, Option<String>) {
+        ("", Some(self.0.clone()))
+    }
+
+    fn is_default(&self) -> bool {
+        false
+    }
+
+    fn compatible_games(&self) -> Option<&'static [u32]> {
+        None
+    }
+
+    fn incompatible_games(&self) -> Option<&'static [u32]> {
+        None
+    }
+
+    fn value_range(&self) -> Option<ValueRange> {
+        None
+    }
+
+    fn requires(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn conflicts_with(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+impl fmt::Display for CustomArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
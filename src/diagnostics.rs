@@ -0,0 +1,65 @@
+//! Structured diagnostics for a fully configured compiler.
+
+use crate::{ValidationError, ValueRange};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Probably unintended, but the compiler will still run.
+    Warning,
+    /// The compiler is likely to fail or misbehave.
+    Error,
+}
+
+/// The specific problem a [`Diagnostic`] describes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// The argument is not supported by the target game.
+    IncompatibleGame { app_id: u32 },
+    /// The argument was selected more than once.
+    Duplicate { count: usize },
+    /// The argument's value lies outside its declared range.
+    OutOfRange { value: String, range: ValueRange },
+    /// A `requires` or `conflicts_with` rule was violated.
+    Rule(ValidationError),
+}
+
+/// A single finding of [`Compiler::validate_for_game`](crate::Compiler::validate_for_game).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The human-readable `name()` of the offending argument.
+    pub arg_name: &'static str,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, arg_name: &'static str, kind: DiagnosticKind) -> Self {
+        Self { severity, arg_name, kind }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: '{}' ", self.severity, self.arg_name)?;
+        match &self.kind {
+            DiagnosticKind::IncompatibleGame { app_id } => write!(f, "is not supported by game {}", app_id),
+            DiagnosticKind::Duplicate { count } => write!(f, "is selected {} times", count),
+            DiagnosticKind::OutOfRange { value, range } => write!(f, "has value {} outside of {}", value, range),
+            DiagnosticKind::Rule(rule) => write!(f, "violates a rule: {}", rule),
+        }
+    }
+}
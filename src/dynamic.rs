@@ -0,0 +1,456 @@
+//! Compiler definitions loaded from TOML at runtime.
+//!
+//! The configs use exactly the same schema as `compiler_configs/`, so a community tool
+//! (a Hammer++ compiler, VRAD-plus, ...) can be supported by shipping a `.toml` file
+//! next to the application instead of recompiling this crate.
+//!
+//! Loaded definitions are leaked and live for the rest of the program, just like the
+//! `'static` metadata of the built-in compilers. Load them once at startup.
+
+use crate::config::{self, CompilerConfig};
+use crate::{Compiler, CompilerArg, ExecutableSpec, ParameterSpec, ParseArgError, PresetSpec, SourceGame, ValueRange, ValueType};
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur while loading a compiler definition.
+#[derive(Debug)]
+pub enum DefinitionError {
+    /// The config file could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The config is not valid TOML or does not match the schema.
+    Toml(toml::de::Error),
+    /// The config is well-formed but violates a constraint (e.g. an unknown `requires` target).
+    Invalid {
+        parameter: String,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read '{}': {}", path.display(), source),
+            Self::Toml(err) => write!(f, "invalid compiler config: {}", err),
+            Self::Invalid { parameter, reason } => write!(f, "invalid parameter '{}': {}", parameter, reason),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Toml(err) => Some(err),
+            Self::Invalid { .. } => None,
+        }
+    }
+}
+
+/// A single parameter of a runtime-loaded compiler.
+#[derive(Debug, PartialEq)]
+pub struct ParameterDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub argument: &'static str,
+    pub value_type: ValueType,
+    pub default_value: Option<&'static str>,
+    pub is_default: bool,
+    pub compatible_games: Option<&'static [u32]>,
+    pub incompatible_games: Option<&'static [u32]>,
+    pub value_range: Option<ValueRange>,
+    pub requires: &'static [&'static str],
+    pub conflicts_with: &'static [&'static str],
+}
+
+/// A compiler definition loaded from a TOML config at runtime.
+#[derive(Debug, PartialEq)]
+pub struct CompilerDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub working_dir: &'static str,
+    pub base_arguments: &'static [&'static str],
+    pub executable: ExecutableSpec,
+    pub parameters: &'static [ParameterDefinition],
+    specs: &'static [ParameterSpec],
+    pub presets: &'static [PresetSpec],
+    game_defaults: &'static [GameDefaults],
+    /// The changes of each preset, in the order of `presets`.
+    preset_changes: &'static [ArgumentChanges],
+}
+
+/// A resolved `[[game_defaults]]` set, see [`DynamicCompiler::with_defaults_for_game`].
+#[derive(Debug, PartialEq)]
+struct GameDefaults {
+    games: Vec<u32>,
+    changes: ArgumentChanges,
+}
+
+/// The resolved `add`/`remove` lists of a `[[game_defaults]]` or `[[presets]]` entry.
+#[derive(Debug, PartialEq)]
+struct ArgumentChanges {
+    /// Switches dropped before `add` is applied.
+    remove: Vec<String>,
+    /// Parameter indices and the values to parse.
+    add: Vec<(usize, Option<String>)>,
+}
+
+impl ArgumentChanges {
+    fn resolve(config: &CompilerConfig, add: &[config::ArgumentRef], remove: &[String]) -> Self {
+        // Added arguments replace existing ones with the same switch; positionals cannot be matched.
+        let remove = remove.iter().cloned()
+            .chain(add.iter().map(|r| r.argument().to_string()).filter(|a| !a.is_empty()))
+            .collect();
+        let add = add.iter()
+            .map(|reference| config.resolve_argument(reference).expect("argument changes are validated when the definition is loaded"))
+            .collect();
+        Self { remove, add }
+    }
+
+    fn apply(&self, definition: &'static CompilerDefinition, args: &mut Vec<DynamicArg>) {
+        args.retain(|arg| !self.remove.iter().any(|r| r == arg.parameter.argument));
+        for (index, value) in &self.add {
+            let arg = DynamicArg::new(&definition.parameters[*index], value.as_deref())
+                .expect("argument changes are validated when the definition is loaded");
+            args.push(arg);
+        }
+    }
+}
+
+/// Resolves a game name from a config against the built-in [`SourceGame`] registry.
+fn lookup_game(name: &str) -> Option<u32> {
+    SourceGame::from_name(name).map(SourceGame::app_id)
+}
+
+impl CompilerDefinition {
+    /// Parses and validates a compiler config from a TOML string.
+    pub fn from_toml_str(content: &str) -> Result<&'static Self, DefinitionError> {
+        let config: CompilerConfig = toml::from_str(content).map_err(DefinitionError::Toml)?;
+        config.validate(&lookup_game).map_err(|(parameter, reason)| DefinitionError::Invalid { parameter, reason })?;
+        Ok(Box::leak(Box::new(Self::from_config(config))))
+    }
+
+    /// Reads and parses a single compiler config file.
+    pub fn load(path: impl AsRef<Path>) -> Result<&'static Self, DefinitionError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| DefinitionError::Io { path: path.to_path_buf(), source })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Loads every `.toml` file in `dir`, sorted by file name.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<&'static Self>, DefinitionError> {
+        let dir = dir.as_ref();
+        let io_error = |source| DefinitionError::Io { path: dir.to_path_buf(), source };
+
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("toml") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        paths.iter().map(Self::load).collect()
+    }
+
+    /// Looks up a parameter by its command-line switch.
+    pub fn find_parameter(&self, argument: &str) -> Option<&'static ParameterDefinition> {
+        self.parameters.iter().find(|p| p.argument == argument)
+    }
+
+    fn from_config(config: CompilerConfig) -> Self {
+        let game_defaults = config.game_defaults.iter().map(|set| GameDefaults {
+            games: config::resolve_games(&set.games, &lookup_game).expect("game names are validated when the definition is loaded"),
+            changes: ArgumentChanges::resolve(&config, &set.add, &set.remove),
+        }).collect();
+        let preset_changes = config.presets.iter()
+            .map(|preset| ArgumentChanges::resolve(&config, &preset.add, &preset.remove))
+            .collect();
+        let presets = config.presets.iter()
+            .map(|preset| PresetSpec { name: leak_str(preset.name.clone()), description: leak_str(preset.description.clone()) })
+            .collect();
+        let base_arguments = config.base_arguments.split_whitespace().map(|s| leak_str(s.to_string())).collect();
+        let executable = config.executable_or_default();
+        let leak_names = |names: Vec<String>| leak_slice(names.into_iter().map(leak_str).collect());
+        let executable = ExecutableSpec {
+            windows: leak_names(executable.windows),
+            linux: leak_names(executable.linux),
+            macos: leak_names(executable.macos),
+            search_dirs: leak_names(executable.search_dirs),
+        };
+
+        let resolve_games = |games: Vec<config::GameRef>| {
+            leak_slice(config::resolve_games(&games, &lookup_game).expect("game names are validated when the definition is loaded"))
+        };
+        let parameters: &'static [ParameterDefinition] = leak_slice(
+            config.parameters.into_iter().map(|p| {
+                let value_range = p.value_bounds().map(|(min, max)| ValueRange { min, max });
+                let constraints = p.constraints.unwrap_or(config::ConstraintsConfig {
+                    compatible_games: None,
+                    incompatible_games: None,
+                    min_value: None,
+                    max_value: None,
+                });
+                ParameterDefinition {
+                    name: leak_str(p.name),
+                    description: leak_str(p.description),
+                    argument: leak_str(p.argument),
+                    value_type: match p.value_type {
+                        config::ValueType::Flag => ValueType::Flag,
+                        config::ValueType::Float => ValueType::Float,
+                        config::ValueType::Integer => ValueType::Integer,
+                        config::ValueType::String => ValueType::String,
+                        config::ValueType::Path => ValueType::Path,
+                    },
+                    default_value: p.default_value.map(leak_str),
+                    is_default: p.is_default,
+                    compatible_games: constraints.compatible_games.map(resolve_games),
+                    incompatible_games: constraints.incompatible_games.map(resolve_games),
+                    value_range,
+                    requires: leak_slice(p.requires.into_iter().map(leak_str).collect()),
+                    conflicts_with: leak_slice(p.conflicts_with.into_iter().map(leak_str).collect()),
+                }
+            }).collect(),
+        );
+
+        let specs = parameters.iter()
+            .map(|p| ParameterSpec { name: p.name, argument: p.argument, value_type: p.value_type })
+            .collect();
+
+        Self {
+            name: leak_str(config.name),
+            description: leak_str(config.description),
+            working_dir: leak_str(config.working_dir),
+            base_arguments: leak_slice(base_arguments),
+            executable,
+            parameters,
+            specs: leak_slice(specs),
+            presets: leak_slice(presets),
+            game_defaults: leak_slice(game_defaults),
+            preset_changes: leak_slice(preset_changes),
+        }
+    }
+}
+
+fn leak_str(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+fn leak_slice<T>(items: Vec<T>) -> &'static [T] {
+    Box::leak(items.into_boxed_slice())
+}
+
+/// The value held by a [`DynamicArg`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamicValue {
+    Flag,
+    Float(f32),
+    Integer(i64),
+    String(String),
+    Path(PathBuf),
+}
+
+/// An argument of a [`DynamicCompiler`], the runtime counterpart of e.g. `VbspArg`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicArg {
+    parameter: &'static ParameterDefinition,
+    value: DynamicValue,
+}
+
+impl DynamicArg {
+    /// Creates an argument for `parameter`, parsing and range-checking `value`
+    /// exactly like the generated `TryFrom<&str>` implementations do.
+    pub fn new(parameter: &'static ParameterDefinition, value: Option<&str>) -> Result<Self, ParseArgError> {
+        let argument = parameter.argument;
+
+        if parameter.value_type == ValueType::Flag {
+            if value.is_some() {
+                return Err(ParseArgError::UnexpectedValue(argument));
+            }
+            return Ok(Self { parameter, value: DynamicValue::Flag });
+        }
+
+        let value_str = value.ok_or(ParseArgError::MissingValue(argument))?;
+        let invalid = || ParseArgError::InvalidValue { argument, value: value_str.to_string() };
+        let (value, numeric) = match parameter.value_type {
+            ValueType::Float => {
+                let val = value_str.parse::<f32>().map_err(|_| invalid())?;
+                (DynamicValue::Float(val), Some(val as f64))
+            }
+            ValueType::Integer => {
+                let val = value_str.parse::<i64>().map_err(|_| invalid())?;
+                (DynamicValue::Integer(val), Some(val as f64))
+            }
+            ValueType::String => (DynamicValue::String(value_str.to_string()), None),
+            ValueType::Path => (DynamicValue::Path(PathBuf::from(value_str)), None),
+            ValueType::Flag => unreachable!(),
+        };
+
+        // Compare in the parameter's own type, matching the generated parsers.
+        if let (Some(range), Some(numeric)) = (parameter.value_range, numeric) {
+            let in_range = match parameter.value_type {
+                ValueType::Float => ValueRange {
+                    min: range.min.map(|min| min as f32 as f64),
+                    max: range.max.map(|max| max as f32 as f64),
+                }.contains(numeric),
+                _ => range.contains(numeric),
+            };
+            if !in_range {
+                return Err(ParseArgError::OutOfRange { argument, value: value_str.to_string(), range });
+            }
+        }
+
+        Ok(Self { parameter, value })
+    }
+
+    /// Returns the definition of the parameter this argument belongs to.
+    pub fn parameter(&self) -> &'static ParameterDefinition {
+        self.parameter
+    }
+
+    /// Returns the value held by this argument.
+    pub fn value(&self) -> &DynamicValue {
+        &self.value
+    }
+}
+
+impl CompilerArg for DynamicArg {
+    fn name(&self) -> &'static str { self.parameter.name }
+    fn description(&self) -> &'static str { self.parameter.description }
+    fn value_type(&self) -> ValueType { self.parameter.value_type }
+    fn get_default_value(&self) -> Option<Self> {
+        let default = self.parameter.default_value?;
+        Self::new(self.parameter, Some(default)).ok()
+    }
+    fn argument(&self) -> &'static str { self.parameter.argument }
+    fn as_arg(&self) -> (&'static str, Option<String>) {
+        let value = match &self.value {
+            DynamicValue::Flag => None,
+            DynamicValue::Float(val) => Some(val.to_string()),
+            DynamicValue::Integer(val) => Some(val.to_string()),
+            DynamicValue::String(val) => Some(val.clone()),
+            DynamicValue::Path(val) => Some(val.to_string_lossy().to_string()),
+        };
+        (self.parameter.argument, value)
+    }
+    fn is_default(&self) -> bool { self.parameter.is_default }
+    fn compatible_games(&self) -> Option<&'static [u32]> { self.parameter.compatible_games }
+    fn incompatible_games(&self) -> Option<&'static [u32]> { self.parameter.incompatible_games }
+    fn value_range(&self) -> Option<ValueRange> { self.parameter.value_range }
+    fn requires(&self) -> &'static [&'static str] { self.parameter.requires }
+    fn conflicts_with(&self) -> &'static [&'static str] { self.parameter.conflicts_with }
+}
+
+impl std::fmt::Display for DynamicArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A compiler backed by a runtime-loaded [`CompilerDefinition`].
+///
+/// There is no `Default`, as there is no definition to fall back to, so the `Compiler`
+/// constructors that need one are not available. Use [`with_defaults`](Self::with_defaults),
+/// [`with_preset`](Self::with_preset) or [`from_command_line_with`](Self::from_command_line_with).
+#[derive(Debug, Clone)]
+pub struct DynamicCompiler {
+    definition: &'static CompilerDefinition,
+    pub selected_args: Vec<DynamicArg>,
+}
+
+impl DynamicCompiler {
+    /// Creates an instance without any arguments selected.
+    pub fn new(definition: &'static CompilerDefinition) -> Self {
+        Self { definition, selected_args: Vec::new() }
+    }
+
+    /// Creates an instance populated with the parameters marked `is_default = true`,
+    /// like `Default::default()` does for the built-in compilers.
+    pub fn with_defaults(definition: &'static CompilerDefinition) -> Self {
+        let selected_args = definition.parameters.iter()
+            .filter(|p| p.is_default)
+            .map(|p| {
+                let value = if p.value_type == ValueType::Flag { None } else { p.default_value };
+                DynamicArg::new(p, value).expect("default values are validated when the definition is loaded")
+            })
+            .collect();
+        Self { definition, selected_args }
+    }
+
+    /// Like [`with_defaults`](Self::with_defaults), adjusted by the config's
+    /// `[[game_defaults]]` sets that apply to `app_id`.
+    pub fn with_defaults_for_game(definition: &'static CompilerDefinition, app_id: u32) -> Self {
+        let mut compiler = Self::with_defaults(definition);
+        for set in definition.game_defaults.iter().filter(|set| set.games.contains(&app_id)) {
+            set.changes.apply(definition, &mut compiler.selected_args);
+        }
+        compiler
+    }
+
+    /// Like [`with_defaults`](Self::with_defaults), with the preset `name` applied.
+    /// Returns `None` if the definition has no such preset.
+    pub fn with_preset(definition: &'static CompilerDefinition, name: &str) -> Option<Self> {
+        let mut compiler = Self::with_defaults(definition);
+        compiler.apply_preset(name).then_some(compiler)
+    }
+
+    /// Parses a full command line (without the executable) for the given definition.
+    pub fn from_command_line_with(definition: &'static CompilerDefinition, line: &str) -> Result<Self, ParseArgError> {
+        let mut compiler = Self::new(definition);
+        compiler.extend_from_args(&crate::command_line::split_windows(line))?;
+        Ok(compiler)
+    }
+
+    /// Returns the definition this compiler was created from.
+    pub fn definition(&self) -> &'static CompilerDefinition {
+        self.definition
+    }
+
+    /// Parses a single `key value` argument, the counterpart of the generated `TryFrom<&str>`.
+    pub fn parse_arg(&self, value: &str) -> Result<DynamicArg, ParseArgError> {
+        let (key, value_opt) =
+            if let Some((k, v)) = value.split_once(' ') {
+                (k, Some(v))
+            } else {
+                (value, None)
+            };
+
+        let parameter = self.definition.find_parameter(key)
+            .ok_or_else(|| ParseArgError::UnknownArgument(key.to_string()))?;
+        DynamicArg::new(parameter, value_opt)
+    }
+}
+
+impl Compiler for DynamicCompiler {
+    type Arg = DynamicArg;
+
+    fn name(&self) -> &'static str { self.definition.name }
+    fn description(&self) -> &'static str { self.definition.description }
+    fn working_dir_template(&self) -> &'static str { self.definition.working_dir }
+    fn base_arguments(&self) -> &'static [&'static str] { self.definition.base_arguments }
+    fn executable(&self) -> ExecutableSpec { self.definition.executable }
+
+    fn parameters(&self) -> &'static [ParameterSpec] { self.definition.specs }
+    fn parse_parameter(&self, index: usize, value: Option<&str>) -> Result<Self::Arg, ParseArgError> {
+        let parameter = self.definition.parameters.get(index)
+            .ok_or_else(|| ParseArgError::UnknownArgument(format!("#{}", index)))?;
+        DynamicArg::new(parameter, value)
+    }
+
+    fn presets(&self) -> &'static [PresetSpec] { self.definition.presets }
+    fn apply_preset(&mut self, name: &str) -> bool {
+        let definition = self.definition;
+        let Some(index) = definition.presets.iter().position(|preset| preset.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        definition.preset_changes[index].apply(definition, &mut self.selected_args);
+        true
+    }
+
+    fn get_args(&self) -> &[Self::Arg] { &self.selected_args }
+    fn add_arg(&mut self, arg: Self::Arg) { self.selected_args.push(arg); }
+    fn clear_args(&mut self) { self.selected_args.clear(); }
+}
@@ -0,0 +1,187 @@
+//! Running a resolved [`CommandInfo`] as a child process, or rendering it as a command line.
+
+use crate::command_line::{self, CMD_SPECIAL, ShellKind};
+use crate::{CommandInfo, ExecutionError};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A single line of output produced by a running compiler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputLine {
+    /// A line written to standard output.
+    Stdout(String),
+    /// A line written to standard error.
+    Stderr(String),
+}
+
+impl OutputLine {
+    /// Returns the text of the line, regardless of which stream it came from.
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Stdout(line) | Self::Stderr(line) => line,
+        }
+    }
+}
+
+/// The outcome of a finished compiler process.
+#[derive(Debug, Clone)]
+pub struct ExecutionResult {
+    /// The human-readable name of the compiler that was run.
+    pub name: &'static str,
+    /// The exit status reported by the operating system.
+    pub status: ExitStatus,
+    /// Wall-clock time between spawning the process and its exit.
+    pub duration: Duration,
+}
+
+impl ExecutionResult {
+    /// Whether the process exited with a zero status code.
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// Returns the exit code, or `None` if the process was terminated by a signal.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.status.code()
+    }
+}
+
+impl CommandInfo {
+    /// Builds a `std::process::Command` for this invocation.
+    ///
+    /// The working directory is only applied when it is non-empty, so configs
+    /// with `working_dir = ""` inherit the current directory of the caller.
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.compiler_path);
+        command.args(&self.args);
+        command.envs(self.env.iter().map(|(key, value)| (key, value)));
+        if !self.working_dir.as_os_str().is_empty() {
+            command.current_dir(&self.working_dir);
+        }
+        command
+    }
+
+    /// Renders the program and arguments as a single line that can be pasted into `shell`.
+    ///
+    /// [`split_command_line`](command_line::split_command_line) turns the line back into exactly
+    /// the program and arguments. For [`ShellKind::Cmd`], the line is also a valid Windows command line for `CreateProcess`,
+    /// unless an argument contains both `"` and one of `& | < > ^ ( )`. Windows program paths
+    /// cannot contain `"`, so such a path is not rendered losslessly. `%` is left as it is:
+    /// `cmd.exe` expands `%NAME%` even inside quotes and has no escape for it at the prompt,
+    /// so a line with such a pair only pastes correctly if `NAME` is not set.
+    /// [`crate::script`] doubles `%` for batch files.
+    ///
+    /// The working directory and environment variables are not part of the line; see
+    /// [`crate::script`] for a runnable script.
+    pub fn to_command_line(&self, shell: ShellKind) -> String {
+        let program = self.compiler_path.to_string_lossy();
+        match shell {
+            ShellKind::Cmd => {
+                // The program name is split without escapes, so it is only ever wrapped in quotes.
+                let mut line = if program.is_empty() || program.contains([' ', '\t']) || program.contains(CMD_SPECIAL) {
+                    format!("\"{}\"", program)
+                } else {
+                    program.into_owned()
+                };
+                for arg in &self.args {
+                    line.push(' ');
+                    command_line::quote_windows(arg, CMD_SPECIAL, &mut line);
+                }
+                command_line::escape_cmd(&line)
+            }
+            // The call operator is needed to run a quoted program path.
+            ShellKind::PowerShell => format!("& {}", command_line::join(&self.command_words(&program), shell)),
+            ShellKind::Posix => command_line::join(&self.command_words(&program), shell),
+        }
+    }
+
+    fn command_words<'a>(&'a self, program: &'a str) -> Vec<&'a str> {
+        std::iter::once(program).chain(self.args.iter().map(String::as_str)).collect()
+    }
+
+    /// Starts the compiler with piped stdout/stderr and returns the child process.
+    pub fn spawn(&self) -> Result<Child, ExecutionError> {
+        if !self.working_dir.as_os_str().is_empty() && !self.working_dir.is_dir() {
+            return Err(ExecutionError::WorkingDirNotFound(self.working_dir.clone()));
+        }
+
+        self.to_command()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| match source.kind() {
+                std::io::ErrorKind::NotFound => ExecutionError::ExecutableNotFound(self.compiler_path.clone()),
+                _ => ExecutionError::Spawn { path: self.compiler_path.clone(), source },
+            })
+    }
+
+    /// Runs the compiler to completion, passing every output line to `on_output` as it arrives.
+    ///
+    /// Both streams are read concurrently, so a compiler that writes heavily to
+    /// stderr cannot dead-lock on a full stdout pipe.
+    pub fn run<F>(&self, mut on_output: F) -> Result<ExecutionResult, ExecutionError>
+    where
+        F: FnMut(OutputLine),
+    {
+        let started = Instant::now();
+        let mut child = self.spawn()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut readers = Vec::with_capacity(2);
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(forward_lines(stdout, sender.clone(), OutputLine::Stdout));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(forward_lines(stderr, sender.clone(), OutputLine::Stderr));
+        }
+        // Drop our own sender so the loop below ends once both readers hang up.
+        drop(sender);
+
+        for line in receiver {
+            on_output(line);
+        }
+
+        // Reap the child before reporting a reader failure, so it is never left behind.
+        let status = child.wait()?;
+        for reader in readers {
+            reader.join().map_err(|_| std::io::Error::other("output reader thread panicked"))??;
+        }
+
+        Ok(ExecutionResult {
+            name: self.name,
+            status,
+            duration: started.elapsed(),
+        })
+    }
+}
+
+/// Reads `stream` line by line on a background thread and sends each line through `sender`.
+/// Invalid UTF-8 is replaced lossily and trailing `\r\n` / `\n` are stripped.
+fn forward_lines<R>(
+    stream: R,
+    sender: mpsc::Sender<OutputLine>,
+    wrap: fn(String) -> OutputLine,
+) -> thread::JoinHandle<std::io::Result<()>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                return Ok(());
+            }
+            while matches!(buffer.last(), Some(b'\n' | b'\r')) {
+                buffer.pop();
+            }
+            // The receiver only goes away if the caller stopped listening; keep draining the pipe.
+            let _ = sender.send(wrap(String::from_utf8_lossy(&buffer).into_owned()));
+        }
+    })
+}
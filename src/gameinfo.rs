@@ -0,0 +1,137 @@
+//! Reading game metadata from a mod directory's `gameinfo.txt`.
+
+use crate::keyvalues::{self, Block, KeyValuesError};
+use crate::CompilerContext;
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur while loading a `gameinfo.txt`.
+#[derive(Debug)]
+pub enum GameInfoError {
+    /// The file could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file is not valid KeyValues.
+    Parse {
+        path: PathBuf,
+        source: KeyValuesError,
+    },
+    /// The document has no top-level `GameInfo` block.
+    MissingGameInfo,
+}
+
+impl std::fmt::Display for GameInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read '{}': {}", path.display(), source),
+            Self::Parse { path, source } => write!(f, "failed to parse '{}': {}", path.display(), source),
+            Self::MissingGameInfo => write!(f, "gameinfo.txt has no \"GameInfo\" block"),
+        }
+    }
+}
+
+impl std::error::Error for GameInfoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            Self::MissingGameInfo => None,
+        }
+    }
+}
+
+/// A single entry of the `SearchPaths` block, e.g. `game+mod  |gameinfo_path|.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPath {
+    /// The path IDs joined by `+` in the key, e.g. `["game", "mod"]`.
+    pub ids: Vec<String>,
+    /// The path as written, possibly containing `|gameinfo_path|` or `|all_source_engine_paths|`.
+    pub path: String,
+}
+
+impl SearchPath {
+    /// Expands the `|gameinfo_path|` and `|all_source_engine_paths|` tokens for a mod in `game_dir`.
+    /// Relative paths are resolved against the parent of `game_dir`, like the engine does.
+    pub fn resolve(&self, game_dir: &Path) -> PathBuf {
+        let engine_dir = game_dir.parent().unwrap_or(game_dir);
+        if let Some(rest) = self.path.strip_prefix("|gameinfo_path|") {
+            game_dir.join(rest)
+        } else if let Some(rest) = self.path.strip_prefix("|all_source_engine_paths|") {
+            engine_dir.join(rest)
+        } else {
+            engine_dir.join(&self.path)
+        }
+    }
+}
+
+/// The metadata of a Source game or mod, as declared in its `gameinfo.txt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameInfo {
+    /// The `game` key, e.g. "Team Fortress 2".
+    pub game: String,
+    /// The `title` key, if present.
+    pub title: Option<String>,
+    /// `FileSystem/SteamAppId`: the App ID the game runs as.
+    pub steam_app_id: Option<u32>,
+    /// `FileSystem/ToolsAppId`: the App ID of the SDK tools.
+    pub tools_app_id: Option<u32>,
+    /// `FileSystem/SearchPaths`, in declaration order.
+    pub search_paths: Vec<SearchPath>,
+}
+
+impl GameInfo {
+    /// Parses the contents of a `gameinfo.txt`.
+    pub fn parse(input: &str) -> Result<Self, GameInfoError> {
+        let document = keyvalues::parse(input).map_err(|source| GameInfoError::Parse { path: PathBuf::new(), source })?;
+        let root = document.get_block("GameInfo").ok_or(GameInfoError::MissingGameInfo)?;
+        Ok(Self::from_block(root))
+    }
+
+    /// Loads `<game_dir>/gameinfo.txt`.
+    pub fn load(game_dir: &Path) -> Result<Self, GameInfoError> {
+        let path = game_dir.join("gameinfo.txt");
+        let text = std::fs::read_to_string(&path).map_err(|source| GameInfoError::Io { path: path.clone(), source })?;
+        Self::parse(&text).map_err(|err| match err {
+            GameInfoError::Parse { source, .. } => GameInfoError::Parse { path, source },
+            err => err,
+        })
+    }
+
+    fn from_block(root: &Block) -> Self {
+        let file_system = root.get_block("FileSystem");
+        let app_id = |key: &str| file_system.and_then(|fs| fs.get_str(key)).and_then(|id| id.trim().parse().ok());
+        let search_paths = file_system
+            .and_then(|fs| fs.get_block("SearchPaths"))
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        Some(SearchPath {
+                            ids: key.split('+').map(str::to_string).collect(),
+                            path: value.as_str()?.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            game: root.get_str("game").unwrap_or_default().to_string(),
+            title: root.get_str("title").map(str::to_string),
+            steam_app_id: app_id("SteamAppId"),
+            tools_app_id: app_id("ToolsAppId"),
+            search_paths,
+        }
+    }
+}
+
+impl CompilerContext {
+    /// Reads `gameinfo.txt` from `game_dir` and stores its `SteamAppId` in `app_id`.
+    /// Returns the discovered App ID, which is `None` if the file does not declare one.
+    pub fn detect_app_id(&mut self) -> Result<Option<u32>, GameInfoError> {
+        let info = GameInfo::load(&self.game_dir)?;
+        self.app_id = info.steam_app_id;
+        Ok(self.app_id)
+    }
+}
@@ -0,0 +1,303 @@
+//! A parser for Valve's KeyValues text format (KeyValues1), as used by `libraryfolders.vdf`,
+//! `appmanifest_*.acf` and `gameinfo.txt`.
+//!
+//! Platform conditionals such as `[$WIN32]` are accepted and ignored, so every entry is kept.
+
+/// A value in a KeyValues document: either a string or a nested block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    Block(Block),
+}
+
+impl Value {
+    /// Returns the string value, or `None` for blocks.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Block(_) => None,
+        }
+    }
+
+    /// Returns the nested block, or `None` for strings.
+    pub fn as_block(&self) -> Option<&Block> {
+        match self {
+            Self::Text(_) => None,
+            Self::Block(block) => Some(block),
+        }
+    }
+}
+
+/// An ordered list of key/value pairs. Keys may repeat and are compared case-insensitively,
+/// matching how the engine reads these files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Block {
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Block {
+    /// Returns the first value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, value)| value)
+    }
+
+    /// Returns the first string value stored under `key`.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// Returns the first block stored under `key`.
+    pub fn get_block(&self, key: &str) -> Option<&Block> {
+        self.get(key).and_then(Value::as_block)
+    }
+
+    /// Iterates over the entries in document order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+/// An error encountered while parsing a KeyValues document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyValuesError {
+    /// The document ended inside a block or before a key received its value.
+    UnexpectedEof,
+    /// A `"` string was not closed before the end of the document.
+    UnterminatedString { line: usize },
+    /// A token appeared where it is not allowed, e.g. a `}` without a matching `{`.
+    UnexpectedToken { line: usize, token: String },
+}
+
+impl std::fmt::Display for KeyValuesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "unexpected end of KeyValues document"),
+            Self::UnterminatedString { line } => write!(f, "unterminated string on line {}", line),
+            Self::UnexpectedToken { line, token } => write!(f, "unexpected '{}' on line {}", token, line),
+        }
+    }
+}
+
+impl std::error::Error for KeyValuesError {}
+
+/// Parses a KeyValues document into its top-level block.
+pub fn parse(input: &str) -> Result<Block, KeyValuesError> {
+    let mut lexer = Lexer { input, pos: 0, line: 1, escapes: true };
+    parse_block(&mut lexer, false)
+}
+
+/// Like [`parse`], but every backslash is literal. Files written by Hammer and the SDK tools
+/// do not use escape sequences, so a path like `C:\tools\new` must not become a tab and a newline.
+pub fn parse_unescaped(input: &str) -> Result<Block, KeyValuesError> {
+    let mut lexer = Lexer { input, pos: 0, line: 1, escapes: false };
+    parse_block(&mut lexer, false)
+}
+
+/// Writes a document in the tab-indented layout the engine's tools use.
+/// It reads back with [`parse`] unchanged.
+pub fn serialize(document: &Block) -> String {
+    let mut out = String::new();
+    write_block(&mut out, document, 0, true);
+    out
+}
+
+/// Writes a document for [`parse_unescaped`]: strings are written verbatim,
+/// so they must not contain `"`.
+pub fn serialize_unescaped(document: &Block) -> String {
+    let mut out = String::new();
+    write_block(&mut out, document, 0, false);
+    out
+}
+
+fn write_block(out: &mut String, block: &Block, depth: usize, escapes: bool) {
+    let indent = "\t".repeat(depth);
+    for (key, value) in &block.entries {
+        out.push_str(&indent);
+        write_quoted(out, key, escapes);
+        match value {
+            Value::Text(text) => {
+                out.push('\t');
+                write_quoted(out, text, escapes);
+                out.push('\n');
+            }
+            Value::Block(inner) => {
+                out.push_str(&format!("\n{indent}{{\n"));
+                write_block(out, inner, depth + 1, escapes);
+                out.push_str(&format!("{indent}}}\n"));
+            }
+        }
+    }
+}
+
+/// Quotes `text`. Backslashes are only escaped where [`parse`] would read them as an escape,
+/// so paths like `$path\$file` stay as they are.
+fn write_quoted(out: &mut String, text: &str, escapes: bool) {
+    out.push('"');
+    if !escapes {
+        out.push_str(text);
+        out.push('"');
+        return;
+    }
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\\' if matches!(chars.peek(), None | Some('"' | '\\' | 'n' | 't')) => out.push_str("\\\\"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(std::borrow::Cow<'a, str>),
+    Open,
+    Close,
+}
+
+struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    /// Whether quoted strings use escape sequences, see [`parse_unescaped`].
+    escapes: bool,
+}
+
+impl<'a> Lexer<'a> {
+    /// Skips whitespace and `//` comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.input[self.pos..];
+            let trimmed = rest.trim_start();
+            self.line += rest[..rest.len() - trimmed.len()].matches('\n').count();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Skips a `[$WIN32]`-style conditional, if one follows.
+    fn skip_condition(&mut self) {
+        self.skip_trivia();
+        let rest = &self.input[self.pos..];
+        if rest.starts_with('[')
+            && let Some(end) = rest.find(']')
+        {
+            self.pos += end + 1;
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, KeyValuesError> {
+        self.skip_trivia();
+        let rest = &self.input[self.pos..];
+        let Some(first) = rest.chars().next() else {
+            return Ok(None);
+        };
+
+        match first {
+            '{' => {
+                self.pos += 1;
+                Ok(Some(Token::Open))
+            }
+            '}' => {
+                self.pos += 1;
+                Ok(Some(Token::Close))
+            }
+            '"' => self.quoted().map(Some),
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, '{' | '}' | '"'))
+                    .unwrap_or(rest.len());
+                self.pos += end;
+                Ok(Some(Token::Text(rest[..end].into())))
+            }
+        }
+    }
+
+    /// Reads a quoted string. `\"`, `\\`, `\n` and `\t` are unescaped;
+    /// any other backslash is kept, so Windows paths survive unescaped files.
+    fn quoted(&mut self) -> Result<Token<'a>, KeyValuesError> {
+        let start_line = self.line;
+        let body = &self.input[self.pos + 1..];
+        let mut chars = body.char_indices();
+        let mut unescaped: Option<String> = None;
+
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += index + 2;
+                    let text = match unescaped {
+                        Some(text) => text.into(),
+                        None => body[..index].into(),
+                    };
+                    return Ok(Token::Text(text));
+                }
+                '\\' if self.escapes => {
+                    let escaped = match chars.clone().next() {
+                        Some((_, '"')) => Some('"'),
+                        Some((_, '\\')) => Some('\\'),
+                        Some((_, 'n')) => Some('\n'),
+                        Some((_, 't')) => Some('\t'),
+                        _ => None,
+                    };
+                    let text = unescaped.get_or_insert_with(|| body[..index].to_string());
+                    match escaped {
+                        Some(escaped) => {
+                            chars.next();
+                            text.push(escaped);
+                        }
+                        None => text.push('\\'),
+                    }
+                }
+                _ => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    if let Some(text) = unescaped.as_mut() {
+                        text.push(c);
+                    }
+                }
+            }
+        }
+
+        Err(KeyValuesError::UnterminatedString { line: start_line })
+    }
+}
+
+fn parse_block(lexer: &mut Lexer<'_>, nested: bool) -> Result<Block, KeyValuesError> {
+    let mut block = Block::default();
+    loop {
+        let key = match lexer.next_token()? {
+            Some(Token::Text(key)) => key.into_owned(),
+            Some(Token::Close) if nested => return Ok(block),
+            None if !nested => return Ok(block),
+            None => return Err(KeyValuesError::UnexpectedEof),
+            Some(token) => return Err(unexpected(lexer, token)),
+        };
+        lexer.skip_condition();
+
+        let value = match lexer.next_token()? {
+            Some(Token::Text(value)) => Value::Text(value.into_owned()),
+            Some(Token::Open) => Value::Block(parse_block(lexer, true)?),
+            None => return Err(KeyValuesError::UnexpectedEof),
+            Some(token) => return Err(unexpected(lexer, token)),
+        };
+        lexer.skip_condition();
+        block.entries.push((key, value));
+    }
+}
+
+fn unexpected(lexer: &Lexer<'_>, token: Token<'_>) -> KeyValuesError {
+    let token = match token {
+        Token::Text(text) => text.into_owned(),
+        Token::Open => "{".to_string(),
+        Token::Close => "}".to_string(),
+    };
+    KeyValuesError::UnexpectedToken { line: lexer.line, token }
+}
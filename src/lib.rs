@@ -1,4 +1,29 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub mod command_line;
+pub use command_line::ShellKind;
+#[cfg(feature = "dynamic")]
+mod config;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+pub mod execution;
+pub use execution::{ExecutionResult, OutputLine};
+pub mod diagnostics;
+pub use diagnostics::{Diagnostic, DiagnosticKind, Severity};
+pub mod pipeline;
+pub use pipeline::{Pipeline, PipelineReport, PipelineStage, StageOutcome, StageReport};
+pub mod wrapper;
+pub use wrapper::Wrapper;
+pub mod keyvalues;
+pub mod steam;
+pub use steam::SteamError;
+pub mod gameinfo;
+pub use gameinfo::{GameInfo, GameInfoError};
+pub mod cmdseq;
+pub mod custom;
+pub mod compilepal;
+pub mod script;
 
 /// Defines the type of value an argument can hold.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,8 +35,35 @@ pub enum ValueType {
     Path,
 }
 
+/// Inclusive numeric bounds of a `Float` or `Integer` argument.
+/// Either side may be open, e.g. `-luxeldensity` only has a maximum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValueRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl ValueRange {
+    /// Checks whether `value` lies within the bounds.
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl std::fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "{}..={}", min, max),
+            (Some(min), None) => write!(f, ">= {}", min),
+            (None, Some(max)) => write!(f, "<= {}", max),
+            (None, None) => write!(f, "any value"),
+        }
+    }
+}
+
 /// Defines the common interface for a compiler tool.
-pub trait Compiler: Default {
+pub trait Compiler: Sized {
     /// The specific argument type associated with this compiler.
     type Arg: CompilerArg;
 
@@ -21,6 +73,24 @@ pub trait Compiler: Default {
     fn description(&self) -> &'static str;
     /// Returns the default working directory for the compiler.
     fn working_dir_template(&self) -> &'static str;
+    /// Returns the executable names and search directories declared in the config.
+    fn executable(&self) -> ExecutableSpec;
+    /// Returns the arguments that are always passed to the compiler, before any configured ones.
+    /// These may contain placeholders (e.g. `$mapPath`) and are not affected by `clear_args`.
+    fn base_arguments(&self) -> &'static [&'static str];
+
+    /// Returns every parameter declared for this compiler, in config order.
+    fn parameters(&self) -> &'static [ParameterSpec];
+    /// Builds the argument for the parameter at `index` in [`parameters`](Self::parameters),
+    /// parsing `value` the same way `TryFrom<&str>` does.
+    fn parse_parameter(&self, index: usize, value: Option<&str>) -> Result<Self::Arg, ParseArgError>;
+
+    /// Returns the presets declared for this compiler, in config order.
+    fn presets(&self) -> &'static [PresetSpec];
+    /// Applies the preset `name` (case-insensitive) to the current arguments: its removed
+    /// switches are dropped and its arguments added, replacing any with the same switch.
+    /// Returns `false` if no such preset exists.
+    fn apply_preset(&mut self, name: &str) -> bool;
 
     /// Returns a slice of the arguments configured for this compiler instance.
     fn get_args(&self) -> &[Self::Arg];
@@ -35,6 +105,7 @@ pub trait Compiler: Default {
             name: self.name(),
             description: self.description(),
             working_dir_template: self.working_dir_template(),
+            base_arguments: self.base_arguments(),
         }
     }
 
@@ -44,11 +115,14 @@ pub trait Compiler: Default {
     }
 
     /// Builds the final, flattened list of command-line arguments for execution.
+    /// Base arguments come first, followed by the configured switches in insertion order.
+    /// Positional arguments (such as the map) come last, as the Source compilers expect.
     fn build_args(&self) -> Vec<String> {
-        let structured_args = self.get_structured_args();
-        let mut final_args = Vec::with_capacity(structured_args.len() * 2);
+        let base_args = self.base_arguments();
+        let mut final_args = Vec::with_capacity(base_args.len() + self.get_args().len() * 2);
+        final_args.extend(base_args.iter().map(|arg| arg.to_string()));
 
-        for (key, value_opt) in structured_args {
+        for (key, value_opt) in ordered_args(self.get_args()).map(CompilerArg::as_arg) {
             if !key.is_empty() {
                 final_args.push(key.to_string());
             }
@@ -61,30 +135,300 @@ pub trait Compiler: Default {
         final_args
     }
 
-    /// Build the final command-line string for execution using context values.///
+    /// Checks the configured arguments against the `requires` and `conflicts_with` rules
+    /// from the compiler config, so problems are reported before anything is launched.
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let args = self.get_args();
+        let is_selected = |switch: &str| args.iter().any(|arg| arg.argument() == switch);
+        let mut errors = Vec::new();
+
+        for arg in args {
+            let argument = arg.argument();
+            for &requires in arg.requires() {
+                let error = ValidationError::MissingRequirement { argument, requires };
+                if !is_selected(requires) && !errors.contains(&error) {
+                    errors.push(error);
+                }
+            }
+            for &conflicts_with in arg.conflicts_with() {
+                // Rules may be declared on both sides; report each pair only once.
+                let error = ValidationError::Conflict { argument, conflicts_with };
+                let mirrored = ValidationError::Conflict { argument: conflicts_with, conflicts_with: argument };
+                if is_selected(conflicts_with) && !errors.contains(&error) && !errors.contains(&mirrored) {
+                    errors.push(error);
+                }
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Checks every configured argument against a target game and returns all findings at once:
+    /// game-incompatible arguments, duplicated arguments, out-of-range values and rule violations.
+    /// An empty list means nothing was found.
+    fn validate_for_game(&self, app_id: u32) -> Vec<Diagnostic> {
+        collect_diagnostics(self, Some(app_id))
+    }
+
+    /// Like [`validate_for_game`](Self::validate_for_game), using the App ID of `context`
+    /// (see [`CompilerContext::detect_app_id`]). Without a known App ID, game compatibility is not checked.
+    fn validate_for_context(&self, context: &CompilerContext) -> Vec<Diagnostic> {
+        collect_diagnostics(self, context.app_id)
+    }
+
+    /// Creates an instance with the default arguments and the preset `name` applied,
+    /// e.g. `Vrad::preset("final")`. Returns `None` if no such preset exists.
+    ///
+    /// Only available for compilers whose `Default` holds the default arguments; for a
+    /// `DynamicCompiler`, use `DynamicCompiler::with_preset`.
+    fn preset(name: &str) -> Option<Self>
+    where
+        Self: Default,
+    {
+        let mut compiler = Self::default();
+        compiler.apply_preset(name).then_some(compiler)
+    }
+
+    /// Parses a full command line (without the executable) into a configured compiler,
+    /// e.g. `-game "C:\x y\csgo" -micro 0.5 -verbose map.vmf`.
+    ///
+    /// The line is split with Windows quoting rules, see [`command_line::split_windows`].
+    /// For a `DynamicCompiler`, use `DynamicCompiler::from_command_line_with`.
+    fn from_command_line(line: &str) -> Result<Self, ParseArgError>
+    where
+        Self: Default,
+    {
+        Self::from_args(&command_line::split_windows(line))
+    }
+
+    /// Rebuilds a compiler from an already tokenized argument list.
+    fn from_args<S: AsRef<str>>(tokens: &[S]) -> Result<Self, ParseArgError>
+    where
+        Self: Default,
+    {
+        let mut compiler = Self::default();
+        compiler.clear_args();
+        compiler.extend_from_args(tokens)?;
+        Ok(compiler)
+    }
+
+    /// Parses a tokenized argument list and appends the resulting arguments to this instance.
+    ///
+    /// Leading base arguments are skipped. Tokens that do not match a switch are assigned
+    /// to positional parameters (`argument = ""`) in declaration order; a positional
+    /// `string` parameter also accepts unknown switches, so nothing is lost.
+    fn extend_from_args<S: AsRef<str>>(&mut self, tokens: &[S]) -> Result<(), ParseArgError> {
+        let tokens: Vec<&str> = tokens.iter().map(AsRef::as_ref).collect();
+        let specs = self.parameters();
+        let positional: Vec<usize> = (0..specs.len()).filter(|&i| specs[i].argument.is_empty()).collect();
+        let mut positional_used = 0;
+
+        let mut rest = &tokens[base_arguments_len(self.base_arguments(), &tokens)..];
+        while let Some(&token) = rest.first() {
+            // Some switches span several words (e.g. `+sv_cheats 1`). They may arrive as one
+            // token (from `build_args`) or split by whitespace; prefer the longest match.
+            let switch = specs
+                .iter()
+                .enumerate()
+                .filter(|(_, spec)| !spec.argument.is_empty())
+                .filter_map(|(index, spec)| {
+                    if spec.argument == token {
+                        return Some((index, spec, 1));
+                    }
+                    let words = spec.argument.split(' ').count();
+                    let matches = rest.len() >= words && spec.argument.split(' ').eq(rest[..words].iter().copied());
+                    matches.then_some((index, spec, words))
+                })
+                .max_by_key(|&(_, _, words)| words);
+
+            if let Some((index, spec, words)) = switch {
+                if spec.value_type == ValueType::Flag {
+                    let arg = self.parse_parameter(index, None)?;
+                    self.add_arg(arg);
+                    rest = &rest[words..];
+                } else {
+                    // Only a string can be another switch; `-game -verbose` lacks the directory.
+                    // Negative numbers still count as values.
+                    let value = rest.get(words).copied().filter(|value| {
+                        spec.value_type == ValueType::String || !is_switch_token(value) || value.parse::<f64>().is_ok()
+                    });
+                    let arg = self.parse_parameter(index, value)?;
+                    self.add_arg(arg);
+                    rest = &rest[(words + 1).min(rest.len())..];
+                }
+                continue;
+            }
+
+            let is_string = |index: &usize| specs[*index].value_type == ValueType::String;
+            let accepts = |index: &usize| !is_switch_token(token) || is_string(index);
+            // Once every positional has a value, only a string catch-all takes further tokens.
+            let index = positional[positional_used.min(positional.len())..]
+                .iter()
+                .find(|index| accepts(index))
+                .or_else(|| positional.iter().rev().find(|index| is_string(index)))
+                .copied()
+                .ok_or_else(|| ParseArgError::UnknownArgument(token.to_string()))?;
+
+            if let Some(position) = positional.iter().position(|&i| i == index) {
+                positional_used = positional_used.max(position + 1);
+            }
+            let arg = self.parse_parameter(index, Some(token))?;
+            self.add_arg(arg);
+            rest = &rest[1..];
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the path of the compiler executable for `context.platform`.
+    ///
+    /// Every search directory is tried with every executable name for the platform, in config
+    /// order, and the first existing file wins. If none exists, the first candidate is returned
+    /// so the caller still gets a meaningful path to report.
+    fn resolve_executable(&self, context: &CompilerContext) -> PathBuf {
+        let spec = self.executable();
+        let names = spec.names(context.platform);
+        let dirs: Vec<PathBuf> = spec.search_dirs.iter().map(|dir| PathBuf::from(context.replace_native(dir))).collect();
+
+        let mut candidates = dirs.iter().flat_map(|dir| names.iter().map(move |name| dir.join(name)));
+        let first = candidates.next().unwrap_or_default();
+        if first.is_file() {
+            return first;
+        }
+        candidates.find(|path| path.is_file()).unwrap_or(first)
+    }
+
+    /// Build the final command-line string for execution using context values.
     /// If provided, `executable` will be used as the compiler path. Otherwise,
-    /// it defaults to `<bin_dir>/<compiler_name>.exe`.
+    /// it is found with [`resolve_executable`](Self::resolve_executable).
+    ///
+    /// Placeholders are rendered in `context.path_style`, and so are the values of
+    /// `ValueType::Path` arguments. Arguments are ordered as in [`build_args`](Self::build_args).
     fn build_command(&self, context: &CompilerContext, executable: Option<PathBuf>) -> CommandInfo {
-        let final_args = self
-            .build_args()
-            .iter()
-            .map(|arg| context.replace(arg))
-            .collect();
-        let resolved_wd = PathBuf::from(context.replace(self.working_dir_template()));
+        let mut final_args: Vec<String> = self.base_arguments().iter().map(|arg| context.replace(arg)).collect();
+        for arg in ordered_args(self.get_args()) {
+            let (key, value_opt) = arg.as_arg();
+            if !key.is_empty() {
+                final_args.push(context.replace(key));
+            }
+            if let Some(value) = value_opt {
+                let value = match arg.value_type() {
+                    ValueType::Path => context.replace_path(&value),
+                    _ => context.replace(&value),
+                };
+                final_args.push(value);
+            }
+        }
+        let resolved_wd = PathBuf::from(context.replace_native(self.working_dir_template()));
 
-        let compiler_path = if let Some(path) = executable {
-            path
-        } else {
-            context.bin_dir.join(&self.name().to_lowercase()).with_extension("exe")
-        };
+        let compiler_path = executable.unwrap_or_else(|| self.resolve_executable(context));
 
         CommandInfo {
             name: self.name(),
-            compiler_path: compiler_path,
+            compiler_path,
             args: final_args,
             working_dir: resolved_wd,
+            env: Vec::new(),
         }
     }
+
+    /// Like [`build_command`](Self::build_command), but fails instead of producing arguments
+    /// such as `-game ""`: every unknown or empty placeholder in the working directory, the base
+    /// arguments and the configured arguments is collected into one [`PlaceholderError`].
+    fn build_command_checked(&self, context: &CompilerContext, executable: Option<PathBuf>) -> Result<CommandInfo, PlaceholderError> {
+        let mut error = PlaceholderError::default();
+        context.check_placeholders(self.working_dir_template(), &mut error);
+        for arg in self.base_arguments() {
+            context.check_placeholders(arg, &mut error);
+        }
+        for arg in self.get_args() {
+            let (key, value_opt) = arg.as_arg();
+            context.check_placeholders(key, &mut error);
+            if let Some(value) = value_opt {
+                context.check_placeholders(&value, &mut error);
+            }
+        }
+
+        if error.is_empty() { Ok(self.build_command(context, executable)) } else { Err(error) }
+    }
+}
+
+/// Whether `token` looks like a switch rather than a value.
+fn is_switch_token(token: &str) -> bool {
+    token.starts_with('-') || token.starts_with('+')
+}
+
+/// Returns the switches of `args` followed by the positional arguments, each in insertion order.
+fn ordered_args<A: CompilerArg>(args: &[A]) -> impl Iterator<Item = &A> {
+    let is_positional = |arg: &&A| arg.argument().is_empty();
+    args.iter().filter(move |arg| !is_positional(arg)).chain(args.iter().filter(is_positional))
+}
+
+/// Shared implementation of [`Compiler::validate_for_game`] and [`Compiler::validate_for_context`].
+fn collect_diagnostics<C: Compiler>(compiler: &C, app_id: Option<u32>) -> Vec<Diagnostic> {
+    let args = compiler.get_args();
+    let mut diagnostics = Vec::new();
+
+    for (index, arg) in args.iter().enumerate() {
+        let name = arg.name();
+        if let Some(app_id) = app_id
+            && !arg.is_compatible_with_game(app_id)
+        {
+            diagnostics.push(Diagnostic::new(Severity::Error, name, DiagnosticKind::IncompatibleGame { app_id }));
+        }
+
+        // Report duplicates once, at the first occurrence.
+        let is_first = !args[..index].iter().any(|prev| prev.name() == name);
+        let count = args.iter().filter(|other| other.name() == name).count();
+        if is_first && count > 1 {
+            diagnostics.push(Diagnostic::new(Severity::Warning, name, DiagnosticKind::Duplicate { count }));
+        }
+
+        if let (Some(range), Some(value)) = (arg.value_range(), arg.as_arg().1)
+            && !arg.is_within_range()
+        {
+            diagnostics.push(Diagnostic::new(Severity::Error, name, DiagnosticKind::OutOfRange { value, range }));
+        }
+    }
+
+    for rule in compiler.validate().err().unwrap_or_default() {
+        let switch = match &rule {
+            ValidationError::MissingRequirement { argument, .. } => *argument,
+            ValidationError::Conflict { argument, .. } => *argument,
+        };
+        let name = args.iter().find(|arg| arg.argument() == switch).map_or(switch, |arg| arg.name());
+        diagnostics.push(Diagnostic::new(Severity::Error, name, DiagnosticKind::Rule(rule)));
+    }
+
+    diagnostics
+}
+
+/// Returns how many leading `tokens` correspond to `base_arguments`, or 0 if they don't match.
+/// Placeholders in base arguments (e.g. `$mapPath`) match any token.
+fn base_arguments_len(base_arguments: &[&str], tokens: &[&str]) -> usize {
+    let matches = base_arguments.len() <= tokens.len()
+        && base_arguments.iter().zip(tokens).all(|(base, token)| base.contains('$') || base == token);
+    if matches { base_arguments.len() } else { 0 }
+}
+
+/// Static description of a single parameter declared in a compiler config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterSpec {
+    /// The human-readable name of the parameter.
+    pub name: &'static str,
+    /// The command-line switch, or `""` for positional parameters.
+    pub argument: &'static str,
+    /// The type of value the parameter takes.
+    pub value_type: ValueType,
+}
+
+/// A named argument set declared in a compiler config, e.g. "fast" or "final".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresetSpec {
+    /// The name passed to [`Compiler::preset`] and [`Compiler::apply_preset`].
+    pub name: &'static str,
+    /// A short description for display, possibly empty.
+    pub description: &'static str,
 }
 
 /// Defines the common interface for a compiler argument.
@@ -97,6 +441,8 @@ pub trait CompilerArg: Sized {
     fn value_type(&self) -> ValueType;
     /// Returns the default value for this argument, if one is defined.
     fn get_default_value(&self) -> Option<Self>;
+    /// Returns the command-line switch of the argument (e.g. `-game`), or `""` for positional arguments.
+    fn argument(&self) -> &'static str;
     /// Formats the argument and its value (if any) into a command-line string.
     fn as_arg(&self) -> (&'static str, Option<String>);
     /// Whether this argument is used by the compiler by default.
@@ -105,12 +451,55 @@ pub trait CompilerArg: Sized {
     /// Returns a slice of game App IDs this argument is compatible with.
     /// Returns `None` if the argument is universally compatible (i.e., has no game constraints).
     fn compatible_games(&self) -> Option<&'static [u32]>;
+    /// Returns a slice of game App IDs this argument must not be used with.
+    /// Returns `None` if the argument is not explicitly excluded from any game.
+    fn incompatible_games(&self) -> Option<&'static [u32]>;
+    /// Returns the allowed numeric range for `Float`/`Integer` arguments, if one is declared.
+    /// Useful for rendering sliders or spin boxes in a GUI.
+    fn value_range(&self) -> Option<ValueRange>;
+    /// Returns the switches (e.g. `-repack`) that must also be selected for this argument to work.
+    fn requires(&self) -> &'static [&'static str];
+    /// Returns the switches that must not be selected together with this argument.
+    fn conflicts_with(&self) -> &'static [&'static str];
+    /// Checks whether the argument's value lies within its declared range.
+    /// Arguments without a range are always within range.
+    fn is_within_range(&self) -> bool {
+        match (self.value_range(), self.as_arg().1) {
+            (Some(range), Some(value)) => value.parse::<f64>().map_or(true, |value| range.contains(value)),
+            _ => true,
+        }
+    }
     /// Checks if this argument is compatible with a specific game App ID.
+    /// The game must be on the allow-list (if any) and must not be on the deny-list (if any).
     fn is_compatible_with_game(&self, app_id: u32) -> bool {
-        match self.compatible_games() {
+        let allowed = match self.compatible_games() {
             Some(games) => games.contains(&app_id),
             None => true,
-        }
+        };
+        let denied = match self.incompatible_games() {
+            Some(games) => games.contains(&app_id),
+            None => false,
+        };
+        allowed && !denied
+    }
+    /// Typed version of [`compatible_games`](Self::compatible_games).
+    /// App IDs that are not in the [`SourceGame`] registry are skipped.
+    fn compatible_source_games(&self) -> Option<Vec<SourceGame>> {
+        self.compatible_games().map(|ids| ids.iter().filter_map(|&id| SourceGame::from_app_id(id)).collect())
+    }
+    /// Typed version of [`incompatible_games`](Self::incompatible_games).
+    /// App IDs that are not in the [`SourceGame`] registry are skipped.
+    fn incompatible_source_games(&self) -> Option<Vec<SourceGame>> {
+        self.incompatible_games().map(|ids| ids.iter().filter_map(|&id| SourceGame::from_app_id(id)).collect())
+    }
+    /// Checks if this argument is compatible with a registered game.
+    fn is_compatible_with(&self, game: SourceGame) -> bool {
+        self.is_compatible_with_game(game.app_id())
+    }
+    /// Checks compatibility with the game of `context`.
+    /// Always `true` if the context's App ID is unknown.
+    fn is_compatible_with_context(&self, context: &CompilerContext) -> bool {
+        context.app_id.is_none_or(|app_id| self.is_compatible_with_game(app_id))
     }
 }
 
@@ -129,6 +518,39 @@ pub struct CompilerContext {
     pub map_name_ext: String,      // Filename with extension (e.g., "de_dust2.vmf")
     pub map_ext: String,           // File extension (e.g., "vmf")
     pub bsp_path: PathBuf,         // Full path to the .bsp file
+
+    /// The platform whose executables should be used. Defaults to the current one.
+    pub platform: Platform,
+    /// How path placeholders are rendered in arguments.
+    pub path_style: PathStyle,
+    /// Custom placeholders, see [`set_var`](Self::set_var).
+    pub vars: HashMap<String, ContextVar>,
+    /// The Steam App ID of the target game, if known. See [`detect_app_id`](Self::detect_app_id).
+    pub app_id: Option<u32>,
+}
+
+/// Names of the placeholders every [`CompilerContext`] provides, without the `$`.
+const BUILTIN_PLACEHOLDERS: &[&str] = &[
+    "binDir", "gameDir", "mapPath", "outDir", "mapDir", "mapNameExt", "mapName", "mapExt", "bspPath", "file", "path",
+];
+
+/// Writes a separator at the start of `rest` in `style` and returns its length in bytes, or 0 if there is none.
+fn push_separator(output: &mut String, rest: &str, style: PathStyle) -> usize {
+    if rest.starts_with(['\\', '/']) {
+        output.push(style.separator());
+        1
+    } else {
+        0
+    }
+}
+
+/// The value of a custom placeholder registered on a [`CompilerContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextVar {
+    /// Inserted verbatim.
+    Text(String),
+    /// Rendered according to the context's [`PathStyle`].
+    Path(PathBuf),
 }
 
 impl CompilerContext {
@@ -160,11 +582,62 @@ impl CompilerContext {
             map_name_ext,
             map_ext,
             bsp_path,
+            platform: Platform::current(),
+            path_style: PathStyle::Native,
+            vars: HashMap::new(),
+            app_id: None,
         }
     }
 
     /// Replaces placeholders in the string in a single pass and returns a new string.
+    /// Path placeholders are rendered according to `path_style`.
+    ///
+    /// Supported syntax:
+    /// * `$name` — the longest known name wins, so `$mapNameExt` is not `$mapName` + `Ext`.
+    /// * `${name}` — an exact name, e.g. `${mapName}Suffix`.
+    /// * `${env:VAR}` — the environment variable `VAR`.
+    /// * `$$` — a literal `$`.
+    ///
+    /// Unknown placeholders are kept as written. A `\` or `/` right after a path placeholder
+    /// is written as the separator of the path style, so `$mapDir\$mapName` works on any host.
     pub fn replace(&self, input: &str) -> String {
+        self.replace_with_style(input, self.path_style, None)
+    }
+
+    /// Like [`replace`](Self::replace), but fails if `input` references an unknown placeholder
+    /// or a placeholder whose value is empty (e.g. `$gameDir` on a default context).
+    pub fn replace_strict(&self, input: &str) -> Result<String, PlaceholderError> {
+        let mut error = PlaceholderError::default();
+        let output = self.replace_with_style(input, self.path_style, Some(&mut error));
+        if error.is_empty() { Ok(output) } else { Err(error) }
+    }
+
+    /// Records the placeholder problems of `input` into `error` without failing,
+    /// so several templates can be checked and reported together.
+    pub fn check_placeholders(&self, input: &str, error: &mut PlaceholderError) {
+        self.replace_with_style(input, self.path_style, Some(error));
+    }
+
+    /// Like [`replace`](Self::replace), but always renders paths natively.
+    /// Used for paths the host itself opens, such as the working directory and executable search.
+    pub fn replace_native(&self, input: &str) -> String {
+        self.replace_with_style(input, PathStyle::Native, None)
+    }
+
+    /// Replaces placeholders in the value of a `Path` argument and renders the whole
+    /// value according to `path_style`, so literal parts get the same separators as placeholders.
+    pub fn replace_path(&self, input: &str) -> String {
+        self.path_style.render(&self.replace(input))
+    }
+
+    /// Renders a path placeholder value in the given style.
+    fn render_path(&self, path: &Path, style: PathStyle) -> String {
+        style.render(&path.to_string_lossy())
+    }
+
+    /// The single-pass replacement behind all `replace*` methods.
+    /// If `report` is given, unknown and empty placeholders are recorded in it.
+    fn replace_with_style(&self, input: &str, style: PathStyle, mut report: Option<&mut PlaceholderError>) -> String {
         // Pre-allocate memory to avoid reallocations.
         // Take the original length + a small buffer.
         let mut output = String::with_capacity(input.len() + 32);
@@ -182,14 +655,68 @@ impl CompilerContext {
 
             // Check what follows the '$'
             let remaining = &input[start_of_match..];
-            let placeholder_value = self.get_placeholder_value(remaining);
+
+            // `$$` is an escaped, literal '$'.
+            if remaining[1..].starts_with('$') {
+                output.push('$');
+                last_match_end = start_of_match + 2;
+                continue;
+            }
+
+            // `${name}` or `${env:VAR}`: the name must match exactly.
+            if let Some(braced) = remaining[1..].strip_prefix('{')
+                && let Some(name_end) = braced.find('}')
+            {
+                let name = &braced[..name_end];
+                let value = match name.strip_prefix("env:") {
+                    Some(var) => std::env::var(var).ok(),
+                    None => self.lookup_placeholder(name, style),
+                };
+                match value {
+                    Some(value) => {
+                        if value.is_empty() && let Some(report) = report.as_deref_mut() {
+                            report.add_empty(name);
+                        }
+                        output.push_str(&value);
+                        last_match_end = start_of_match + name_end + 3; // +3 for '$', '{' and '}'
+                        if self.is_path_placeholder(name) {
+                            last_match_end += push_separator(&mut output, &input[last_match_end..], style);
+                        }
+                    }
+                    None => {
+                        if let Some(report) = report.as_deref_mut() {
+                            report.add_unresolved(name);
+                        }
+                        // Unknown names are kept as written, like unknown `$name` placeholders.
+                        output.push_str(&remaining[..name_end + 3]);
+                        last_match_end = start_of_match + name_end + 3;
+                    }
+                }
+                continue;
+            }
+
+            let placeholder_value = self.get_placeholder_value(remaining, style);
 
             if let Some((placeholder_key, value)) = placeholder_value {
+                if value.is_empty() && let Some(report) = report.as_deref_mut() {
+                    report.add_empty(placeholder_key);
+                }
                 // Placeholder found, append its value
                 output.push_str(&value);
                 // Advance the cursor by the length of the placeholder (e.g., "$mapName")
                 last_match_end = start_of_match + placeholder_key.len() + 1; // +1 for '$'
+                if self.is_path_placeholder(placeholder_key) {
+                    last_match_end += push_separator(&mut output, &input[last_match_end..], style);
+                }
             } else {
+                // Only a '$' followed by a name counts as a placeholder; `$` or `$5` is plain text.
+                let starts_name = remaining[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+                let name_len = remaining[1..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(remaining.len() - 1);
+                if starts_name && let Some(report) = report.as_deref_mut() {
+                    report.add_unresolved(&remaining[1..=name_len]);
+                }
                 // This is not a placeholder, just a '$' character. Add it as is.
                 output.push('$');
                 last_match_end = start_of_match + 1;
@@ -204,36 +731,182 @@ impl CompilerContext {
         output
     }
 
+    /// Returns the registered game matching `app_id`, if any.
+    pub fn game(&self) -> Option<SourceGame> {
+        self.app_id.and_then(SourceGame::from_app_id)
+    }
+
+    /// Sets a custom placeholder, e.g. `set_var("mapCopyLocation", ...)` makes `$mapCopyLocation`
+    /// available. The value is inserted verbatim. Custom names take precedence over built-in ones.
+    pub fn set_var(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.vars.insert(name.into(), ContextVar::Text(value.into()));
+    }
+
+    /// Sets a custom path placeholder. Unlike [`set_var`](Self::set_var), the value is
+    /// rendered according to `path_style`, like the built-in path placeholders.
+    pub fn set_path_var(&mut self, name: impl Into<String>, path: impl Into<PathBuf>) {
+        self.vars.insert(name.into(), ContextVar::Path(path.into()));
+    }
+
+    /// Removes a custom placeholder, returning its previous value.
+    pub fn remove_var(&mut self, name: &str) -> Option<ContextVar> {
+        self.vars.remove(name)
+    }
+
+    /// Returns the value of a built-in placeholder by name (without the `$`).
+    fn builtin_value(&self, name: &str, style: PathStyle) -> Option<String> {
+        let value = match name {
+            "binDir" => self.render_path(&self.bin_dir, style),
+            "gameDir" => self.render_path(&self.game_dir, style),
+            "mapPath" => self.render_path(&self.map_path, style),
+            "outDir" => self.render_path(&self.out_dir, style),
+            "mapDir" => self.render_path(&self.map_dir, style),
+            "mapNameExt" => self.map_name_ext.clone(),
+            "mapName" => self.map_name.clone(),
+            "mapExt" => self.map_ext.clone(),
+            "bspPath" => self.render_path(&self.bsp_path, style),
+            // Aliases
+            "file" => self.map_name.clone(),
+            "path" => self.render_path(&self.map_path, style),
+            _ => return None,
+        };
+        Some(value)
+    }
+
     /// Helper function for matching a string slice with known placeholders.
+    /// The longest matching name wins, so `$mapNameExt` is never read as `$mapName` + `Ext`.
     /// Returns (key, value) on success.
-    fn get_placeholder_value(&self, remaining_slice: &str) -> Option<(&str, String)> {
-        // We use starts_with, which is a very fast operation for &str
-        if remaining_slice.starts_with("$binDir") {
-            Some(("binDir", self.bin_dir.to_string_lossy().into_owned()))
-        } else if remaining_slice.starts_with("$gameDir") {
-            Some(("gameDir", self.game_dir.to_string_lossy().into_owned()))
-        } else if remaining_slice.starts_with("$mapPath") {
-            Some(("mapPath", self.map_path.to_string_lossy().into_owned()))
-        } else if remaining_slice.starts_with("$outDir") {
-            Some(("outDir", self.out_dir.to_string_lossy().into_owned()))
-        } else if remaining_slice.starts_with("$mapDir") {
-            Some(("mapDir", self.map_dir.to_string_lossy().into_owned()))
-        } else if remaining_slice.starts_with("$mapNameExt") {
-            Some(("mapNameExt", self.map_name_ext.clone()))
-        } else if remaining_slice.starts_with("$mapName") { // Important: $mapNameExt must come before $mapName
-            Some(("mapName", self.map_name.clone()))
-        } else if remaining_slice.starts_with("$mapExt") {
-            Some(("mapExt", self.map_ext.clone()))
-        } else if remaining_slice.starts_with("$bspPath") {
-            Some(("bspPath", self.bsp_path.to_string_lossy().into_owned()))
+    fn get_placeholder_value<'a>(&'a self, remaining_slice: &str, style: PathStyle) -> Option<(&'a str, String)> {
+        let name = remaining_slice.strip_prefix('$')?;
+        let key = BUILTIN_PLACEHOLDERS
+            .iter()
+            .copied()
+            .chain(self.vars.keys().map(String::as_str))
+            .filter(|key| !key.is_empty() && name.starts_with(key))
+            .max_by_key(|key| key.len())?;
+
+        Some((key, self.lookup_placeholder(key, style)?))
+    }
+
+    /// Whether the placeholder called exactly `name` holds a path, preferring custom ones.
+    fn is_path_placeholder(&self, name: &str) -> bool {
+        match self.vars.get(name) {
+            Some(var) => matches!(var, ContextVar::Path(_)),
+            None => matches!(name, "binDir" | "gameDir" | "mapPath" | "outDir" | "mapDir" | "bspPath" | "path"),
+        }
+    }
+
+    /// Returns the value of the placeholder called exactly `name`, preferring custom ones.
+    fn lookup_placeholder(&self, name: &str, style: PathStyle) -> Option<String> {
+        match self.vars.get(name) {
+            Some(ContextVar::Text(text)) => Some(text.clone()),
+            Some(ContextVar::Path(path)) => Some(self.render_path(path, style)),
+            None => self.builtin_value(name, style),
         }
-        // Aliases
-        else if remaining_slice.starts_with("$file") {
-            Some(("file", self.map_name.clone()))
-        } else if remaining_slice.starts_with("$path") {
-            Some(("path", self.map_path.to_string_lossy().into_owned()))
+    }
+}
+
+/// An operating system a compiler executable is built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub enum Platform {
+    Windows,
+    Linux,
+    MacOs,
+}
+
+impl Platform {
+    /// Returns the platform this library was compiled for.
+    pub const fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Self::Windows
+        } else if cfg!(target_os = "macos") {
+            Self::MacOs
         } else {
-            None
+            Self::Linux
+        }
+    }
+}
+
+impl Default for Platform {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+/// Where to look for a compiler executable, as declared in the `[executable]` table of a config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutableSpec {
+    /// Candidate file names on Windows, in order of preference (e.g. `vbsp.exe`).
+    pub windows: &'static [&'static str],
+    /// Candidate file names on Linux (e.g. `vbsp_linux`).
+    pub linux: &'static [&'static str],
+    /// Candidate file names on macOS.
+    pub macos: &'static [&'static str],
+    /// Directories to search, in order. May contain placeholders such as `$binDir`.
+    pub search_dirs: &'static [&'static str],
+}
+
+impl ExecutableSpec {
+    /// Returns the candidate names for `platform`.
+    /// Falls back to the Windows names when a tool has no native build for the platform.
+    pub fn names(&self, platform: Platform) -> &'static [&'static str] {
+        let names = match platform {
+            Platform::Windows => self.windows,
+            Platform::Linux => self.linux,
+            Platform::MacOs => self.macos,
+        };
+        if names.is_empty() { self.windows } else { names }
+    }
+
+    /// Whether `file_name` is one of the candidate names on any platform.
+    /// The comparison ignores ASCII case, and a missing `.exe` suffix is accepted.
+    pub fn matches(&self, file_name: &str) -> bool {
+        self.windows.iter().chain(self.linux).chain(self.macos).any(|name| {
+            let stem = name.len().checked_sub(4).and_then(|end| {
+                name.get(end..).filter(|ext| ext.eq_ignore_ascii_case(".exe"))?;
+                name.get(..end)
+            });
+            name.eq_ignore_ascii_case(file_name) || stem.is_some_and(|stem| stem.eq_ignore_ascii_case(file_name))
+        })
+    }
+}
+
+/// How paths are written when placeholders and `Path` arguments are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathStyle {
+    /// Paths are written as-is for the host OS.
+    #[default]
+    Native,
+    /// All separators become `\`, e.g. for a Windows command line generated on Linux.
+    Windows,
+    /// All separators become `/`, e.g. for a Linux command line generated on Windows.
+    Posix,
+    /// Absolute POSIX paths are mapped onto Wine's `Z:` drive, e.g. `/home/me/map.vmf`
+    /// becomes `Z:\home\me\map.vmf`. Use this when running Windows compilers through Wine or Proton.
+    WineDrive,
+}
+
+impl PathStyle {
+    /// The separator paths are written with in this style.
+    pub fn separator(self) -> char {
+        match self {
+            Self::Native => std::path::MAIN_SEPARATOR,
+            Self::Windows | Self::WineDrive => '\\',
+            Self::Posix => '/',
+        }
+    }
+
+    /// Rewrites `path` in this style. Drive letters and relative paths are kept;
+    /// only separators (and, for `WineDrive`, the `Z:` root) are changed.
+    pub fn render(self, path: &str) -> String {
+        match self {
+            Self::Native => path.to_string(),
+            Self::Windows => path.replace('/', "\\"),
+            Self::Posix => path.replace('\\', "/"),
+            Self::WineDrive if path.starts_with('/') => format!("Z:{}", path.replace('/', "\\")),
+            Self::WineDrive => path.replace('/', "\\"),
         }
     }
 }
@@ -247,6 +920,8 @@ pub struct CommandInfo {
     pub args: Vec<String>,
     /// The final working directory for the command.
     pub working_dir: PathBuf,
+    /// Extra environment variables for the process, e.g. `WINEPREFIX`.
+    pub env: Vec<(String, String)>,
 }
 
 /// A struct for convenience, serialization, and data transfer.
@@ -259,9 +934,12 @@ pub struct CompilerMetadata {
     pub description: &'static str,
     /// The default working directory for the compiler.
     pub working_dir_template: &'static str,
+    /// Arguments that are always passed to the compiler.
+    #[cfg_attr(feature = "serialization", serde(skip_deserializing))]
+    pub base_arguments: &'static [&'static str],
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseArgError {
     /// The provided argument was not recognized by the compiler.
     UnknownArgument(String),
@@ -274,6 +952,12 @@ pub enum ParseArgError {
         argument: &'static str,
         value: String,
     },
+    /// The value provided for a numeric argument was outside its allowed range.
+    OutOfRange {
+        argument: &'static str,
+        value: String,
+        range: ValueRange,
+    },
 }
 
 impl std::fmt::Display for ParseArgError {
@@ -283,10 +967,123 @@ impl std::fmt::Display for ParseArgError {
             Self::MissingValue(arg) => write!(f, "argument '{}' requires a value, but none was provided", arg),
             Self::UnexpectedValue(arg) => write!(f, "argument '{}' is a flag and does not accept a value", arg),
             Self::InvalidValue { argument, value } => write!(f, "invalid value '{}' for argument '{}'", value, argument),
+            Self::OutOfRange { argument, value, range } => write!(f, "value '{}' for argument '{}' is out of range ({})", value, argument, range),
         }
     }
 }
 
 impl std::error::Error for ParseArgError {}
 
+/// A violation of the dependency rules between a compiler's selected arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// `argument` is selected, but the argument it depends on is not.
+    MissingRequirement {
+        argument: &'static str,
+        requires: &'static str,
+    },
+    /// Two mutually exclusive arguments are selected at the same time.
+    Conflict {
+        argument: &'static str,
+        conflicts_with: &'static str,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingRequirement { argument, requires } => write!(f, "argument '{}' requires '{}'", argument, requires),
+            Self::Conflict { argument, conflicts_with } => write!(f, "argument '{}' cannot be used together with '{}'", argument, conflicts_with),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Placeholders that could not be substituted by [`CompilerContext::replace_strict`]
+/// or [`Compiler::build_command_checked`]. Names are listed once each, without the `$`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaceholderError {
+    /// Placeholders that are neither built in nor registered with `set_var`.
+    pub unresolved: Vec<String>,
+    /// Placeholders that are known but resolved to an empty value.
+    pub empty: Vec<String>,
+}
+
+impl PlaceholderError {
+    /// Whether no problems were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.unresolved.is_empty() && self.empty.is_empty()
+    }
+
+    fn add_unresolved(&mut self, name: &str) {
+        if !self.unresolved.iter().any(|n| n == name) {
+            self.unresolved.push(name.to_string());
+        }
+    }
+
+    fn add_empty(&mut self, name: &str) {
+        if !self.empty.iter().any(|n| n == name) {
+            self.empty.push(name.to_string());
+        }
+    }
+}
+
+impl std::fmt::Display for PlaceholderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |names: &[String]| names.iter().map(|n| format!("${}", n)).collect::<Vec<_>>().join(", ");
+        match (self.unresolved.is_empty(), self.empty.is_empty()) {
+            (false, false) => write!(f, "unresolved placeholders: {}; empty placeholders: {}", list(&self.unresolved), list(&self.empty)),
+            (false, true) => write!(f, "unresolved placeholders: {}", list(&self.unresolved)),
+            (true, false) => write!(f, "empty placeholders: {}", list(&self.empty)),
+            (true, true) => write!(f, "no placeholder errors"),
+        }
+    }
+}
+
+impl std::error::Error for PlaceholderError {}
+
+/// Errors that can occur while launching or supervising a compiler process.
+#[derive(Debug)]
+pub enum ExecutionError {
+    /// The compiler executable does not exist at the resolved path.
+    ExecutableNotFound(PathBuf),
+    /// The resolved working directory does not exist.
+    WorkingDirNotFound(PathBuf),
+    /// The operating system refused to start the process.
+    Spawn {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// An I/O error occurred while reading the process output or waiting for it to exit.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExecutableNotFound(path) => write!(f, "compiler executable not found: {}", path.display()),
+            Self::WorkingDirNotFound(path) => write!(f, "working directory not found: {}", path.display()),
+            Self::Spawn { path, source } => write!(f, "failed to start '{}': {}", path.display(), source),
+            Self::Io(err) => write!(f, "I/O error while running compiler: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Spawn { source, .. } => Some(source),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ExecutionError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
 include!(concat!(env!("OUT_DIR"), "/generated_compilers.rs"));
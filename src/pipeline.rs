@@ -0,0 +1,182 @@
+//! Running several compilers in sequence against one [`CompilerContext`].
+
+use crate::{CommandInfo, CompilerContext, CompilerEnum, ExecutionError, ExecutionResult, OutputLine, Wrapper};
+use std::path::PathBuf;
+
+/// A single step of a [`Pipeline`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineStage {
+    /// The configured compiler to run.
+    pub compiler: CompilerEnum,
+    /// Overrides the executable path, which may contain placeholders.
+    /// If `None`, the default path from `build_command` is used.
+    pub executable: Option<PathBuf>,
+    /// If `true`, a failure of this stage does not stop the pipeline.
+    pub continue_on_error: bool,
+}
+
+impl PipelineStage {
+    pub fn new(compiler: impl Into<CompilerEnum>) -> Self {
+        Self {
+            compiler: compiler.into(),
+            executable: None,
+            continue_on_error: false,
+        }
+    }
+
+    /// Sets an explicit executable path for this stage.
+    pub fn with_executable(mut self, executable: impl Into<PathBuf>) -> Self {
+        self.executable = Some(executable.into());
+        self
+    }
+
+    /// Lets the pipeline carry on with the next stage if this one fails.
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// Builds the command of this stage, resolving placeholders in the executable override too.
+    pub fn build_command(&self, context: &CompilerContext) -> CommandInfo {
+        let executable = self.executable.as_ref().map(|path| PathBuf::from(context.replace_native(&path.to_string_lossy())));
+        self.compiler.build_command(context, executable)
+    }
+}
+
+impl From<CompilerEnum> for PipelineStage {
+    fn from(compiler: CompilerEnum) -> Self {
+        Self::new(compiler)
+    }
+}
+
+/// What happened to a single stage when the pipeline was run.
+#[derive(Debug)]
+pub enum StageOutcome {
+    /// The compiler ran and exited with a zero status code.
+    Succeeded(ExecutionResult),
+    /// The compiler ran but exited with a non-zero status code.
+    Failed(ExecutionResult),
+    /// The compiler could not be launched.
+    Error(ExecutionError),
+    /// The stage was not run because an earlier stage failed.
+    Skipped,
+}
+
+impl StageOutcome {
+    /// Whether the stage ran and succeeded.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Succeeded(_))
+    }
+}
+
+/// The result of a single stage, tagged with the compiler name.
+#[derive(Debug)]
+pub struct StageReport {
+    pub name: &'static str,
+    pub outcome: StageOutcome,
+}
+
+/// Per-stage results of a [`Pipeline::run`] call, in stage order.
+#[derive(Debug)]
+pub struct PipelineReport {
+    pub stages: Vec<StageReport>,
+}
+
+impl PipelineReport {
+    /// Whether every stage ran and succeeded.
+    pub fn success(&self) -> bool {
+        self.stages.iter().all(|stage| stage.outcome.is_success())
+    }
+
+    /// Returns the first stage that did not succeed, ignoring skipped stages.
+    pub fn first_failure(&self) -> Option<&StageReport> {
+        self.stages
+            .iter()
+            .find(|stage| !matches!(stage.outcome, StageOutcome::Succeeded(_) | StageOutcome::Skipped))
+    }
+}
+
+/// An ordered list of compiler stages, e.g. VBSP → VVIS → VRAD → BSPZIP → GAME.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pipeline {
+    pub stages: Vec<PipelineStage>,
+    /// Launches every stage through Wine or Proton. If `None`, stages run natively.
+    pub wrapper: Option<Wrapper>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a stage to the end of the pipeline.
+    pub fn add_stage(&mut self, stage: impl Into<PipelineStage>) {
+        self.stages.push(stage.into());
+    }
+
+    /// Returns the configured stages.
+    pub fn get_stages(&self) -> &[PipelineStage] {
+        &self.stages
+    }
+
+    /// Removes all stages.
+    pub fn clear_stages(&mut self) {
+        self.stages.clear();
+    }
+
+    /// Sets (or clears) the wrapper that every stage is launched through.
+    pub fn set_wrapper(&mut self, wrapper: Option<Wrapper>) {
+        self.wrapper = wrapper;
+    }
+
+    /// Runs every stage in order against `context`.
+    ///
+    /// `on_output` receives the name of the running compiler along with each output line.
+    /// The pipeline stops at the first failing stage unless that stage has
+    /// `continue_on_error` set; the remaining stages are reported as [`StageOutcome::Skipped`].
+    ///
+    /// With a wrapper set, `context` is adapted via [`Wrapper::prepare_context`] before
+    /// the commands are built.
+    pub fn run<F>(&self, context: &CompilerContext, mut on_output: F) -> PipelineReport
+    where
+        F: FnMut(&'static str, OutputLine),
+    {
+        let mut reports = Vec::with_capacity(self.stages.len());
+        let mut halted = false;
+
+        let mut wrapped_context;
+        let context = match &self.wrapper {
+            Some(wrapper) => {
+                wrapped_context = context.clone();
+                wrapper.prepare_context(&mut wrapped_context);
+                &wrapped_context
+            }
+            None => context,
+        };
+
+        for stage in &self.stages {
+            let name = stage.compiler.name();
+            if halted {
+                reports.push(StageReport { name, outcome: StageOutcome::Skipped });
+                continue;
+            }
+
+            let mut command = stage.build_command(context);
+            if let Some(wrapper) = &self.wrapper {
+                command = wrapper.wrap(&command);
+            }
+            let outcome = match command.run(|line| on_output(name, line)) {
+                Ok(result) if result.success() => StageOutcome::Succeeded(result),
+                Ok(result) => StageOutcome::Failed(result),
+                Err(err) => StageOutcome::Error(err),
+            };
+
+            halted = !outcome.is_success() && !stage.continue_on_error;
+            reports.push(StageReport { name, outcome });
+        }
+
+        PipelineReport { stages: reports }
+    }
+}
@@ -0,0 +1,119 @@
+//! Exporting resolved commands as a `.bat`, PowerShell or `sh` script.
+//!
+//! Each [`CommandInfo`] becomes one step: a change into its working directory, its environment
+//! variables, the quoted command, and a check that stops the script with the exit code of a
+//! failed step. The directory and variables of a step do not carry over to the next one.
+//!
+//! ```text
+//! # VBSP
+//! (cd '/maps/my maps' && /sdk/bin/vbsp -game /sdk/tf '/maps/my maps/ctf_test') || exit $?
+//! ```
+
+use crate::command_line::{self, ShellKind};
+use crate::CommandInfo;
+use std::path::Path;
+
+/// Renders `commands` as a script for `shell`, running them in order.
+///
+/// `.bat` scripts use CRLF line endings; PowerShell and `sh` scripts use LF.
+pub fn render(commands: &[CommandInfo], shell: ShellKind) -> String {
+    let mut lines: Vec<String> = match shell {
+        ShellKind::Cmd => vec!["@echo off".into()],
+        // Turns a program that cannot be started into an error that ends the script;
+        // `$LASTEXITCODE` is not set in that case.
+        ShellKind::PowerShell => vec!["$ErrorActionPreference = 'Stop'".into()],
+        ShellKind::Posix => vec!["#!/bin/sh".into()],
+    };
+    for command in commands {
+        lines.push(String::new());
+        match shell {
+            ShellKind::Cmd => render_cmd(command, &mut lines),
+            ShellKind::PowerShell => render_powershell(command, &mut lines),
+            ShellKind::Posix => render_posix(command, &mut lines),
+        }
+    }
+
+    let newline = if shell == ShellKind::Cmd { "\r\n" } else { "\n" };
+    let mut script = lines.join(newline);
+    script.push_str(newline);
+    script
+}
+
+/// Writes the script rendered by [`render`] to `path`. On Unix, `sh` scripts are made executable.
+pub fn save(path: impl AsRef<Path>, commands: &[CommandInfo], shell: ShellKind) -> std::io::Result<()> {
+    let path = path.as_ref();
+    std::fs::write(path, render(commands, shell))?;
+    #[cfg(unix)]
+    if shell == ShellKind::Posix {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Each step runs between `setlocal` and `endlocal`, so its directory and variables
+/// do not leak into the next step.
+fn render_cmd(command: &CommandInfo, lines: &mut Vec<String>) {
+    // Batch files expand `%` even inside quotes; `%%` is a literal percent sign.
+    let quote = |arg: &str| command_line::quote(arg, ShellKind::Cmd).replace('%', "%%");
+
+    lines.push(format!("rem {}", command.name));
+    lines.push("setlocal".into());
+    for (key, value) in &command.env {
+        lines.push(format!("set \"{}={}\"", key, value.replace('%', "%%")));
+    }
+    if !command.working_dir.as_os_str().is_empty() {
+        // A missing directory would otherwise run the step where the previous one ran.
+        lines.push(format!("cd /d {} || exit /b 1", quote(&command.working_dir.to_string_lossy())));
+    }
+    lines.push(command.to_command_line(ShellKind::Cmd).replace('%', "%%"));
+    lines.push("if %errorlevel% neq 0 exit /b %errorlevel%".into());
+    lines.push("endlocal".into());
+}
+
+fn render_powershell(command: &CommandInfo, lines: &mut Vec<String>) {
+    lines.push(format!("# {}", command.name));
+    // `Push-Location` is used even without a directory, so every step ends with `Pop-Location`.
+    if command.working_dir.as_os_str().is_empty() {
+        lines.push("Push-Location".into());
+    } else {
+        lines.push(format!("Push-Location -LiteralPath {}", quote_always_powershell(&command.working_dir.to_string_lossy())));
+    }
+    // Variables are set for the whole session, so the previous values are restored afterwards.
+    if !command.env.is_empty() {
+        lines.push("$savedEnv = @{}".into());
+    }
+    for (key, value) in &command.env {
+        lines.push(format!("$savedEnv[{}] = $env:{}", quote_always_powershell(key), key));
+        lines.push(format!("$env:{} = {}", key, quote_always_powershell(value)));
+    }
+    lines.push(command.to_command_line(ShellKind::PowerShell));
+    // Assignments do not change `$LASTEXITCODE`, so the step can be undone before the check.
+    // Assigning `$null` removes a variable that did not exist before.
+    for (key, _) in &command.env {
+        lines.push(format!("$env:{} = $savedEnv[{}]", key, quote_always_powershell(key)));
+    }
+    lines.push("Pop-Location".into());
+    lines.push("if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }".into());
+}
+
+/// Each step runs in a subshell, so its `cd` does not leak into the next step.
+fn render_posix(command: &CommandInfo, lines: &mut Vec<String>) {
+    let quote = |arg: &str| command_line::quote(arg, ShellKind::Posix);
+
+    lines.push(format!("# {}", command.name));
+    let cd = if command.working_dir.as_os_str().is_empty() {
+        String::new()
+    } else {
+        format!("cd {} && ", quote(&command.working_dir.to_string_lossy()))
+    };
+    // Assignments in front of a command only apply to that command.
+    let env: String = command.env.iter().map(|(key, value)| format!("{}={} ", key, quote(value))).collect();
+    lines.push(format!("({}{}{}) || exit $?", cd, env, command.to_command_line(ShellKind::Posix)));
+}
+
+/// Quotes a value in an assignment or cmdlet parameter, where a bare word would not be a string.
+fn quote_always_powershell(value: &str) -> String {
+    let quoted = command_line::quote(value, ShellKind::PowerShell);
+    if quoted.starts_with('\'') { quoted } else { format!("'{}'", quoted) }
+}
@@ -0,0 +1,206 @@
+//! Locating Source games in a Steam installation.
+
+use crate::keyvalues::{self, KeyValuesError};
+use crate::{CompilerContext, SourceGame};
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur while looking up a game in a Steam installation.
+#[derive(Debug)]
+pub enum SteamError {
+    /// No Steam installation was found in the usual locations.
+    SteamNotFound,
+    /// A Steam file could not be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A Steam file is not valid KeyValues.
+    Parse {
+        path: PathBuf,
+        source: KeyValuesError,
+    },
+    /// None of the library folders contains a manifest for the App ID.
+    AppNotInstalled(u32),
+    /// The game is installed, but no directory containing `gameinfo.txt` was found in it.
+    GameDirNotFound(PathBuf),
+}
+
+impl std::fmt::Display for SteamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SteamNotFound => write!(f, "no Steam installation found"),
+            Self::Io { path, source } => write!(f, "failed to read '{}': {}", path.display(), source),
+            Self::Parse { path, source } => write!(f, "failed to parse '{}': {}", path.display(), source),
+            Self::AppNotInstalled(app_id) => write!(f, "app {} is not installed in any Steam library", app_id),
+            Self::GameDirNotFound(path) => write!(f, "no game directory with a gameinfo.txt found in '{}'", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for SteamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The parts of an `appmanifest_<id>.acf` needed to locate a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppManifest {
+    pub app_id: u32,
+    /// The display name, e.g. "Counter-Strike Global Offensive".
+    pub name: String,
+    /// The folder name below `steamapps/common`.
+    pub install_dir: String,
+    /// The library folder containing the manifest.
+    pub library: PathBuf,
+}
+
+impl AppManifest {
+    /// The absolute install path, `<library>/steamapps/common/<install_dir>`.
+    pub fn install_path(&self) -> PathBuf {
+        self.library.join("steamapps").join("common").join(&self.install_dir)
+    }
+}
+
+/// Returns the Steam installation directory in its default location for the current platform.
+pub fn default_steam_root() -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = if cfg!(windows) {
+        ["ProgramFiles(x86)", "ProgramFiles"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .map(|dir| PathBuf::from(dir).join("Steam"))
+            .collect()
+    } else {
+        let home = PathBuf::from(std::env::var_os("HOME")?);
+        if cfg!(target_os = "macos") {
+            vec![home.join("Library/Application Support/Steam")]
+        } else {
+            vec![
+                home.join(".steam/steam"),
+                home.join(".local/share/Steam"),
+                home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+            ]
+        }
+    };
+    candidates.into_iter().find(|dir| dir.join("steamapps").is_dir())
+}
+
+/// Returns every library folder of the Steam installation at `steam_root`, starting with the root itself.
+///
+/// Both the current `libraryfolders.vdf` layout (`"0" { "path" "..." }`) and the
+/// legacy one (`"1" "D:\\Games"`) are understood. A missing file means only the root library exists.
+pub fn library_folders(steam_root: &Path) -> Result<Vec<PathBuf>, SteamError> {
+    let mut folders = vec![steam_root.to_path_buf()];
+    let path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    if !path.is_file() {
+        return Ok(folders);
+    }
+
+    let document = read_keyvalues(&path)?;
+    let Some(libraries) = document.get_block("libraryfolders") else {
+        return Ok(folders);
+    };
+
+    for (key, value) in libraries.iter() {
+        // Numbered entries are libraries; keys like "contentstatsid" are not.
+        if key.parse::<u32>().is_err() {
+            continue;
+        }
+        let folder = match value {
+            keyvalues::Value::Text(path) => PathBuf::from(path),
+            keyvalues::Value::Block(block) => match block.get_str("path") {
+                Some(path) => PathBuf::from(path),
+                None => continue,
+            },
+        };
+        if !folders.contains(&folder) {
+            folders.push(folder);
+        }
+    }
+    Ok(folders)
+}
+
+/// Finds the manifest of `app_id` across all library folders of the Steam installation at `steam_root`.
+pub fn find_app(steam_root: &Path, app_id: u32) -> Result<AppManifest, SteamError> {
+    for library in library_folders(steam_root)? {
+        let path = library.join("steamapps").join(format!("appmanifest_{}.acf", app_id));
+        if !path.is_file() {
+            continue;
+        }
+
+        let document = read_keyvalues(&path)?;
+        let Some(state) = document.get_block("AppState") else {
+            continue;
+        };
+        let Some(install_dir) = state.get_str("installdir") else {
+            continue;
+        };
+        return Ok(AppManifest {
+            app_id,
+            name: state.get_str("name").unwrap_or_default().to_string(),
+            install_dir: install_dir.to_string(),
+            library,
+        });
+    }
+    Err(SteamError::AppNotInstalled(app_id))
+}
+
+/// Returns the mod directory of a game install, e.g. `.../Team Fortress 2/tf`.
+///
+/// Games in the [`SourceGame`] registry map directly onto their folder. Otherwise the first sub-directory
+/// (alphabetically) that contains a `gameinfo.txt` is used, skipping the shared `hl2` folder
+/// unless it is the only candidate.
+pub fn find_game_dir(install_path: &Path, app_id: u32) -> Result<PathBuf, SteamError> {
+    if let Some(game) = SourceGame::from_app_id(app_id) {
+        let dir = install_path.join(game.game_dir());
+        if dir.join("gameinfo.txt").is_file() {
+            return Ok(dir);
+        }
+    }
+
+    let entries = std::fs::read_dir(install_path).map_err(|source| SteamError::Io { path: install_path.to_path_buf(), source })?;
+    let mut candidates: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|dir| dir.join("gameinfo.txt").is_file())
+        .collect();
+    candidates.sort();
+
+    let is_shared = |dir: &PathBuf| dir.file_name().is_some_and(|name| name.eq_ignore_ascii_case("hl2"));
+    candidates
+        .iter()
+        .find(|dir| !is_shared(dir))
+        .or_else(|| candidates.first())
+        .cloned()
+        .ok_or_else(|| SteamError::GameDirNotFound(install_path.to_path_buf()))
+}
+
+fn read_keyvalues(path: &Path) -> Result<keyvalues::Block, SteamError> {
+    let text = std::fs::read_to_string(path).map_err(|source| SteamError::Io { path: path.to_path_buf(), source })?;
+    keyvalues::parse(&text).map_err(|source| SteamError::Parse { path: path.to_path_buf(), source })
+}
+
+impl CompilerContext {
+    /// Builds a context for an installed Steam game, using the default Steam location.
+    /// See [`from_steam_root`](Self::from_steam_root).
+    pub fn from_steam(app_id: u32, map_path: Option<PathBuf>) -> Result<Self, SteamError> {
+        let steam_root = default_steam_root().ok_or(SteamError::SteamNotFound)?;
+        Self::from_steam_root(&steam_root, app_id, map_path)
+    }
+
+    /// Builds a context for the game `app_id` installed in any library of the Steam
+    /// installation at `steam_root`. `bin_dir` is the game's tools folder (`<install>/bin` unless
+    /// the [`SourceGame`] registry says otherwise), `game_dir` is the
+    /// mod directory found by [`find_game_dir`] and `app_id` is set to `app_id`.
+    pub fn from_steam_root(steam_root: &Path, app_id: u32, map_path: Option<PathBuf>) -> Result<Self, SteamError> {
+        let install_path = find_app(steam_root, app_id)?.install_path();
+        let game_dir = find_game_dir(&install_path, app_id)?;
+        let tools_dir = SourceGame::from_app_id(app_id).map_or("bin", SourceGame::tools_dir);
+        let mut context = Self::new(Some(install_path.join(tools_dir)), Some(game_dir), map_path, None);
+        context.app_id = Some(app_id);
+        Ok(context)
+    }
+}
@@ -0,0 +1,83 @@
+//! Running Windows compilers on other platforms through Wine or Proton.
+
+use crate::{CommandInfo, CompilerContext, PathStyle, Platform};
+use std::path::PathBuf;
+
+/// A compatibility layer that a [`CommandInfo`] can be launched through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialization", derive(serde::Serialize, serde::Deserialize))]
+pub enum Wrapper {
+    /// Plain Wine: `wine <compiler> <args>`.
+    Wine {
+        /// The Wine launcher, usually just `wine`.
+        executable: PathBuf,
+        /// Passed as `WINEPREFIX`. If `None`, Wine uses its default prefix.
+        prefix: Option<PathBuf>,
+    },
+    /// Valve's Proton: `proton run <compiler> <args>`.
+    Proton {
+        /// The `proton` script inside a Proton installation.
+        executable: PathBuf,
+        /// Passed as `STEAM_COMPAT_DATA_PATH`; the prefix lives in `<compat_data_path>/pfx`.
+        compat_data_path: PathBuf,
+        /// Passed as `STEAM_COMPAT_CLIENT_INSTALL_PATH`. Newer Proton versions require it.
+        steam_client_path: Option<PathBuf>,
+    },
+}
+
+impl Wrapper {
+    /// Wine from `PATH`, optionally with a custom prefix.
+    pub fn wine(prefix: Option<PathBuf>) -> Self {
+        Self::Wine { executable: PathBuf::from("wine"), prefix }
+    }
+
+    /// A Proton installation, using `compat_data_path` as its prefix directory.
+    pub fn proton(executable: impl Into<PathBuf>, compat_data_path: impl Into<PathBuf>) -> Self {
+        Self::Proton {
+            executable: executable.into(),
+            compat_data_path: compat_data_path.into(),
+            steam_client_path: None,
+        }
+    }
+
+    /// Adjusts `context` so that commands built from it suit this wrapper:
+    /// Windows executables are resolved and paths are rendered on Wine's `Z:` drive.
+    pub fn prepare_context(&self, context: &mut CompilerContext) {
+        context.platform = Platform::Windows;
+        context.path_style = PathStyle::WineDrive;
+    }
+
+    /// Returns a copy of `command` that is launched through this wrapper.
+    ///
+    /// The compiler path is passed to the wrapper unchanged, as both Wine and Proton
+    /// accept host paths for the program. The working directory is kept as-is.
+    pub fn wrap(&self, command: &CommandInfo) -> CommandInfo {
+        let compiler = command.compiler_path.to_string_lossy().into_owned();
+        let mut env = command.env.clone();
+
+        let (launcher, mut args) = match self {
+            Self::Wine { executable, prefix } => {
+                if let Some(prefix) = prefix {
+                    env.push(("WINEPREFIX".to_string(), prefix.to_string_lossy().into_owned()));
+                }
+                (executable.clone(), vec![compiler])
+            }
+            Self::Proton { executable, compat_data_path, steam_client_path } => {
+                env.push(("STEAM_COMPAT_DATA_PATH".to_string(), compat_data_path.to_string_lossy().into_owned()));
+                if let Some(client) = steam_client_path {
+                    env.push(("STEAM_COMPAT_CLIENT_INSTALL_PATH".to_string(), client.to_string_lossy().into_owned()));
+                }
+                (executable.clone(), vec!["run".to_string(), compiler])
+            }
+        };
+        args.extend(command.args.iter().cloned());
+
+        CommandInfo {
+            name: command.name,
+            compiler_path: launcher,
+            args,
+            working_dir: command.working_dir.clone(),
+            env,
+        }
+    }
+}
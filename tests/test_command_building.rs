@@ -36,11 +36,11 @@ fn test_contextual_command_building() {
 
     let expected_args: Vec<String> = vec![
         "-game", r"C:\CustomGame\game",
-        r"C:\maps\my_map_final.vmf",
         "-verbose",
         "-onlyents",
         "-micro", "0.5",
         "-staticpropcombine_mininstances", "10",
+        r"C:\maps\my_map_final.vmf",
     ].into_iter().map(String::from).collect();
     assert_eq!(command_info.args, expected_args);
 
@@ -109,11 +109,11 @@ fn test_default_value_arguments() {
     let expected_args: Vec<String> = vec![
         "-game",
         &context.game_dir.display().to_string(),
-        &context.map_path.display().to_string(),
         "-micro",
         "1",
         "-staticpropcombine_mininstances",
         "3",
+        &context.map_path.display().to_string(),
     ]
     .into_iter()
     .map(String::from)
@@ -134,7 +134,7 @@ fn test_flag_arguments() {
     let command_info = compiler.build_command(&context, Some(executable_path.clone()));
 
     let expected_args: Vec<String> =
-        vec!["-game", "", "", "-onlyents", "-nowater", "-verbose"]
+        vec!["-game", "", "-onlyents", "-nowater", "-verbose", ""]
             .into_iter()
             .map(String::from)
             .collect();
@@ -177,14 +177,165 @@ fn test_negative_number_arguments() {
     let expected_args: Vec<String> = vec![
         "-game",
         "",
-        "",
         "-micro",
         "-0.1",
         "-staticpropcombine_mininstances",
         "-5",
+        "",
     ]
     .into_iter()
     .map(String::from)
     .collect();
     assert_eq!(command_info.args, expected_args);
 }
+
+/// Test 3.8: Verifies per-platform executable resolution with fallback search directories.
+#[test]
+fn test_executable_resolution() {
+    use valve_compilers::Platform;
+    use valve_compilers::game::Game;
+
+    let root = std::env::temp_dir().join(format!("valve_compilers_exe_{}", std::process::id()));
+    let bin_dir = root.join("bin");
+    std::fs::create_dir_all(bin_dir.join("win64")).unwrap();
+    std::fs::write(bin_dir.join("win64").join("vbsp.exe"), "").unwrap();
+    std::fs::write(bin_dir.join("vbsp_linux"), "").unwrap();
+    std::fs::write(root.join("csgo.exe"), "").unwrap();
+
+    let mut context = CompilerContext::new(Some(bin_dir.clone()), None, None, None);
+
+    // Windows: not in bin/, found in the 64-bit folder.
+    context.platform = Platform::Windows;
+    assert_eq!(Vbsp::default().resolve_executable(&context), bin_dir.join("win64").join("vbsp.exe"));
+    // The game binary is found next to bin/, under its real name.
+    let game_path = Game::default().build_command(&context, None).compiler_path;
+    assert_eq!(game_path, bin_dir.join("..").join("csgo.exe"));
+
+    // Linux: native build of the tool.
+    context.platform = Platform::Linux;
+    assert_eq!(Vbsp::default().resolve_executable(&context), bin_dir.join("vbsp_linux"));
+    // No native BSPZIP build: falls back to the Windows name in the first search directory.
+    let bspzip = valve_compilers::bspzip::Bspzip::default();
+    assert_eq!(bspzip.resolve_executable(&context), bin_dir.join("bspzip.exe"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test 3.9: Verifies that `PathStyle` applies to both path placeholders and `Path` argument values.
+#[test]
+fn test_path_style_rendering() {
+    use valve_compilers::PathStyle;
+
+    let mut compiler = Vbsp::new();
+    compiler.clear_args();
+    compiler.add_arg(VbspArg::GameDirectory(PathBuf::from("$gameDir/custom")));
+    compiler.add_arg(VbspArg::MapFile(PathBuf::from(r"C:\maps\test.vmf")));
+    compiler.add_arg(VbspArg::Verbose);
+
+    let mut context = CompilerContext {
+        game_dir: PathBuf::from("/srv/sdk/csgo"),
+        ..Default::default()
+    };
+    let executable = Some(PathBuf::from("vbsp"));
+
+    context.path_style = PathStyle::Windows;
+    let command = compiler.build_command(&context, executable.clone());
+    assert_eq!(command.args, vec!["-game", r"\srv\sdk\csgo\custom", "-verbose", r"C:\maps\test.vmf"]);
+
+    context.path_style = PathStyle::Posix;
+    let command = compiler.build_command(&context, executable.clone());
+    assert_eq!(command.args, vec!["-game", "/srv/sdk/csgo/custom", "-verbose", "C:/maps/test.vmf"]);
+
+    context.path_style = PathStyle::WineDrive;
+    let command = compiler.build_command(&context, executable.clone());
+    assert_eq!(command.args, vec!["-game", r"Z:\srv\sdk\csgo\custom", "-verbose", r"C:\maps\test.vmf"]);
+
+    context.path_style = PathStyle::Native;
+    let command = compiler.build_command(&context, executable);
+    let game_dir = format!("/srv/sdk/csgo{}custom", std::path::MAIN_SEPARATOR);
+    assert_eq!(command.args, vec!["-game", game_dir.as_str(), "-verbose", r"C:\maps\test.vmf"]);
+
+    // A separator after a path placeholder follows the style; other separators are left alone.
+    context.path_style = PathStyle::Posix;
+    assert_eq!(context.replace(r"$gameDir\maps\x ${gameDir}\y $mapName\z"), r"/srv/sdk/csgo/maps\x /srv/sdk/csgo/y \z");
+    context.path_style = PathStyle::Windows;
+    assert_eq!(context.replace("$gameDir/maps"), r"\srv\sdk\csgo\maps");
+}
+
+/// Test 3.10: Verifies custom placeholders, including longest-match against built-in names.
+#[test]
+fn test_custom_placeholders() {
+    use valve_compilers::vbsp_info::VbspInfo;
+    use valve_compilers::vpk::Vpk;
+
+    let mut context = CompilerContext::new(None, None, Some(PathBuf::from("/maps/test.vmf")), None);
+    context.set_path_var("mapCopyLocation", "/tmp/copy/test.bsp");
+    context.set_var("filePath", "pak01_dir.vpk");
+    context.set_var("mapNameSuffix", "_final");
+
+    let command = VbspInfo::default().build_command(&context, Some(PathBuf::from("vbsp")));
+    assert_eq!(command.args, vec!["-treeinfo", "/tmp/copy/test.bsp"]);
+
+    // `$filePath` must not be read as the `$file` alias followed by "Path".
+    let command = Vpk::default().build_command(&context, Some(PathBuf::from("vpk")));
+    assert_eq!(command.args, vec!["pak01_dir.vpk"]);
+
+    assert_eq!(context.replace("$mapName-$mapNameSuffix-$file"), "test-_final-test");
+    assert_eq!(context.replace("$unknown"), "$unknown");
+
+    context.path_style = valve_compilers::PathStyle::Windows;
+    assert_eq!(context.replace("$mapCopyLocation"), r"\tmp\copy\test.bsp");
+
+    context.remove_var("filePath");
+    assert_eq!(context.replace("$filePath"), "testPath");
+}
+
+/// Test 3.11: Verifies strict placeholder replacement and `build_command_checked`.
+#[test]
+fn test_strict_placeholders() {
+    use valve_compilers::PlaceholderError;
+
+    let context = CompilerContext::new(
+        Some(PathBuf::from("/sdk/bin")),
+        None,
+        Some(PathBuf::from("/maps/test.vmf")),
+        None,
+    );
+
+    assert_eq!(context.replace_strict("$mapName.bsp costs $5").unwrap(), "test.bsp costs $5");
+
+    let err = context.replace_strict("$gameDir/$unknown/$gameDir/$other_one").unwrap_err();
+    assert_eq!(err.unresolved, vec!["unknown", "other_one"]);
+    assert_eq!(err.empty, vec!["gameDir"]);
+    assert_eq!(err.to_string(), "unresolved placeholders: $unknown, $other_one; empty placeholders: $gameDir");
+
+    // The default VBSP arguments include `-game $gameDir`, which is empty here.
+    let err = Vbsp::default().build_command_checked(&context, None).unwrap_err();
+    assert_eq!(err, PlaceholderError { unresolved: vec![], empty: vec!["gameDir".to_string()] });
+
+    let mut context = context;
+    context.game_dir = PathBuf::from("/sdk/csgo");
+    let command = Vbsp::default().build_command_checked(&context, Some(PathBuf::from("vbsp"))).unwrap();
+    assert_eq!(command.args, vec!["-game", "/sdk/csgo", "/maps/test.vmf"]);
+}
+
+/// Test 3.12: Verifies braced placeholders, the `$$` escape and environment lookups.
+#[test]
+fn test_braced_and_escaped_placeholders() {
+    let context = CompilerContext::new(None, None, Some(PathBuf::from("/maps/test.vmf")), None);
+
+    assert_eq!(context.replace("${mapName}Suffix"), "testSuffix");
+    assert_eq!(context.replace("$mapNameSuffix"), "testSuffix");
+    assert_eq!(context.replace("$$mapName costs $$5"), "$mapName costs $5");
+    assert_eq!(context.replace("$$$mapName"), "$test");
+    assert_eq!(context.replace("${unknown} ${mapName"), "${unknown} ${mapName");
+
+    // Cargo sets this variable for the test process as well.
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    assert_eq!(context.replace("${env:CARGO_MANIFEST_DIR}/maps"), format!("{}/maps", manifest_dir));
+    assert_eq!(context.replace("${env:VALVE_COMPILERS_SURELY_UNSET}"), "${env:VALVE_COMPILERS_SURELY_UNSET}");
+
+    let err = context.replace_strict("${mapNameExt}${nope}${env:VALVE_COMPILERS_SURELY_UNSET}$$gameDir").unwrap_err();
+    assert_eq!(err.unresolved, vec!["nope", "env:VALVE_COMPILERS_SURELY_UNSET"]);
+    assert!(err.empty.is_empty());
+}
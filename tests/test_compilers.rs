@@ -1,4 +1,4 @@
-use valve_compilers::{Compiler, CompilerArg};
+use valve_compilers::Compiler;
 use valve_compilers::vbsp::{Vbsp, VbspArg};
 
 /// Test 1.1: Validate the static metadata of a specific compiler.
@@ -85,6 +85,7 @@ fn test_serialization_round_trip() {
 #[cfg(feature = "enum_iter")]
 fn test_csgo_compatibility() {
     use strum::IntoEnumIterator;
+    use valve_compilers::CompilerArg;
     let current_game_id = 730; // CS:GO
 
     for arg_variant in VbspArg::iter() {
@@ -96,3 +97,212 @@ fn test_csgo_compatibility() {
         );
     }
 }
+
+/// Test 1.7: Verifies that base arguments are exposed and emitted before configured arguments.
+#[test]
+fn test_base_arguments() {
+    use valve_compilers::vbsp_info::VbspInfo;
+
+    let compiler = VbspInfo::default();
+    assert_eq!(compiler.name(), "VBSP Info");
+    assert_eq!(compiler.base_arguments(), &["-treeinfo", "$mapCopyLocation"]);
+    assert_eq!(compiler.get_metadata().base_arguments, compiler.base_arguments());
+    assert!(compiler.get_args().is_empty());
+
+    let expected: Vec<String> = vec!["-treeinfo".to_string(), "$mapCopyLocation".to_string()];
+    assert_eq!(compiler.build_args(), expected);
+
+    // Compilers without base arguments are unaffected.
+    assert!(Vbsp::new().base_arguments().is_empty());
+}
+
+/// Test 1.8: Verifies that `incompatible_games` deny-lists are respected.
+#[test]
+fn test_incompatible_games() {
+    use valve_compilers::CompilerArg;
+    use valve_compilers::vrad::VradArg;
+
+    assert_eq!(VradArg::Ldr.incompatible_games(), Some(&[730, 550][..]));
+    assert!(!VradArg::Ldr.is_compatible_with_game(730));
+    assert!(!VradArg::Both.is_compatible_with_game(550));
+    assert!(VradArg::Both.is_compatible_with_game(440));
+
+    // Arguments without a deny-list are unaffected.
+    assert_eq!(VradArg::Hdr.incompatible_games(), None);
+    assert!(VradArg::Hdr.is_compatible_with_game(730));
+
+    // Allow-lists keep working alongside deny-lists.
+    assert!(VradArg::StaticPropBounce.is_compatible_with_game(730));
+    assert!(!VradArg::StaticPropBounce.is_compatible_with_game(440));
+}
+
+/// Test 1.9: Verifies that `requires` and `conflicts_with` rules are reported by `validate()`.
+#[test]
+fn test_argument_rules_validation() {
+    use valve_compilers::ValidationError;
+    use valve_compilers::bspzip::{Bspzip, BspzipArg};
+    use valve_compilers::vrad::{Vrad, VradArg};
+
+    // Missing prerequisite.
+    let mut bspzip = Bspzip::new();
+    bspzip.add_arg(BspzipArg::Compress);
+    assert_eq!(
+        bspzip.validate(),
+        Err(vec![ValidationError::MissingRequirement { argument: "-compress", requires: "-repack" }])
+    );
+    bspzip.add_arg(BspzipArg::Repack);
+    assert_eq!(bspzip.validate(), Ok(()));
+
+    // Mutually exclusive arguments, declared on both sides, are reported once per pair.
+    let mut vrad = Vrad::default();
+    assert_eq!(vrad.validate(), Ok(()));
+    vrad.add_arg(VradArg::Ldr);
+    vrad.add_arg(VradArg::Hdr);
+    vrad.add_arg(VradArg::Both);
+    assert_eq!(
+        vrad.validate(),
+        Err(vec![
+            ValidationError::Conflict { argument: "-ldr", conflicts_with: "-hdr" },
+            ValidationError::Conflict { argument: "-ldr", conflicts_with: "-both" },
+            ValidationError::Conflict { argument: "-hdr", conflicts_with: "-both" },
+        ])
+    );
+}
+
+/// Test 1.10: Verifies that `validate_for_game()` reports every problem in a single list.
+#[test]
+fn test_validate_for_game() {
+    use valve_compilers::{DiagnosticKind, Severity};
+    use valve_compilers::vrad::{Vrad, VradArg};
+
+    let mut vrad = Vrad::default();
+    assert!(vrad.validate_for_game(730).is_empty());
+
+    vrad.add_arg(VradArg::Both);
+    vrad.add_arg(VradArg::Hdr);
+    vrad.add_arg(VradArg::Fast);
+    vrad.add_arg(VradArg::Fast);
+    vrad.add_arg(VradArg::LuxelDensity(4.0));
+
+    let diagnostics = vrad.validate_for_game(730);
+    let summary: Vec<(Severity, &str)> = diagnostics.iter().map(|d| (d.severity, d.arg_name)).collect();
+    assert_eq!(summary, vec![
+        (Severity::Error, "Both"),
+        (Severity::Warning, "Fast"),
+        (Severity::Error, "Luxel Density"),
+        (Severity::Error, "HDR"),
+    ]);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::IncompatibleGame { app_id: 730 });
+    assert_eq!(diagnostics[1].kind, DiagnosticKind::Duplicate { count: 2 });
+    assert!(matches!(&diagnostics[2].kind, DiagnosticKind::OutOfRange { value, .. } if value == "4"));
+    assert!(matches!(diagnostics[3].kind, DiagnosticKind::Rule(_)));
+
+    // TF2 supports -both, so only the remaining findings are left.
+    assert_eq!(vrad.validate_for_game(440).len(), 3);
+}
+
+/// Test 1.11: Verifies the `SourceGame` registry and typed game constraints.
+#[test]
+fn test_source_game_registry() {
+    use valve_compilers::{CompilerArg, CompilerContext, SourceGame};
+    use valve_compilers::bspzip::BspzipArg;
+    use valve_compilers::vrad::VradArg;
+
+    assert_eq!(SourceGame::Tf2.app_id(), 440);
+    assert_eq!(SourceGame::Tf2.game_dir(), "tf");
+    assert_eq!(SourceGame::Tf2.tools_dir(), "bin");
+    assert_eq!(SourceGame::Tf2.to_string(), "Team Fortress 2");
+    assert_eq!(SourceGame::from_app_id(730), Some(SourceGame::Csgo));
+    assert_eq!(SourceGame::from_app_id(1), None);
+    assert_eq!(SourceGame::from_name("CSS"), Some(SourceGame::Css));
+    assert_eq!(SourceGame::from_name("cstrike"), Some(SourceGame::Css));
+    assert_eq!(SourceGame::from_name("garry's mod"), Some(SourceGame::Gmod));
+    assert!(SourceGame::ALL.iter().all(|&game| SourceGame::from_app_id(game.app_id()) == Some(game)));
+
+    // Names in the configs resolve to the same App IDs as before.
+    assert_eq!(VradArg::Both.incompatible_games(), Some(&[730, 550][..]));
+    assert_eq!(VradArg::Both.incompatible_source_games(), Some(vec![SourceGame::Csgo, SourceGame::L4d2]));
+    let compress = BspzipArg::Compress;
+    assert_eq!(compress.compatible_source_games(), Some(vec![SourceGame::Tf2, SourceGame::Css, SourceGame::Momentum]));
+    assert!(compress.is_compatible_with(SourceGame::Momentum));
+    assert!(!compress.is_compatible_with(SourceGame::Csgo));
+
+    let context = CompilerContext { app_id: Some(620), ..Default::default() };
+    assert_eq!(context.game(), Some(SourceGame::Portal2));
+}
+
+/// Test 1.12: Verifies per-game default arguments from `[[game_defaults]]`.
+#[test]
+fn test_default_for_game() {
+    use valve_compilers::SourceGame;
+    use valve_compilers::vrad::{Vrad, VradArg};
+
+    // Games without overrides get the plain defaults.
+    assert_eq!(Vrad::default_for_game(730).get_args(), Vrad::default().get_args());
+    assert_eq!(Vbsp::default_for_game(440).get_args(), Vbsp::default().get_args());
+
+    let tf2 = Vrad::default_for_game(SourceGame::Tf2.app_id());
+    assert_eq!(&tf2.get_args()[2..], &[VradArg::Both, VradArg::StaticPropLighting]);
+    // The overrides are added after the map, but it is still passed last.
+    assert_eq!(tf2.build_args(), ["-game", "$gameDir", "-both", "-StaticPropLighting", "$mapPath"]);
+
+    let css = Vrad::default_for_game(SourceGame::Css.app_id());
+    assert_eq!(&css.get_args()[2..], &[VradArg::Both]);
+
+    // Every override set must be valid for the games it targets.
+    for game in SourceGame::ALL {
+        let vrad = Vrad::default_for_game(game.app_id());
+        assert!(vrad.validate_for_game(game.app_id()).is_empty(), "invalid defaults for {}", game);
+    }
+}
+
+/// Test 1.13: Verifies that `[[presets]]` are enumerable and switch cleanly between each other.
+#[test]
+fn test_presets() {
+    use valve_compilers::CompilerEnum;
+    use valve_compilers::vrad::{Vrad, VradArg};
+    use valve_compilers::vvis::Vvis;
+
+    let names: Vec<&str> = Vrad::default().presets().iter().map(|preset| preset.name).collect();
+    assert_eq!(names, ["fast", "normal", "final"]);
+    assert!(Vbsp::default().presets().is_empty());
+    assert!(Vbsp::preset("fast").is_none());
+
+    let fast = Vrad::preset("Fast").unwrap();
+    // Preset switches go before the map, which the compilers expect as the last argument.
+    assert_eq!(fast.build_args()[2..], ["-fast", "-bounce", "2", "$mapPath"]);
+    assert_eq!(Vvis::preset("fast").unwrap().build_args()[2..], ["-fast", "$mapPath"]);
+    assert_eq!(Vrad::preset("normal").unwrap().get_args(), Vrad::default().get_args());
+
+    // Presets apply on top of the current arguments and undo each other.
+    let mut vrad = Vrad::default_for_game(440);
+    assert!(vrad.apply_preset("fast"));
+    assert_eq!(&vrad.get_args()[2..], &[VradArg::Both, VradArg::Fast, VradArg::Bounces(2)]);
+    assert!(vrad.apply_preset("final"));
+    assert_eq!(
+        &vrad.get_args()[2..],
+        &[VradArg::Both, VradArg::Final, VradArg::StaticPropLighting, VradArg::TextureShadows]
+    );
+    assert!(vrad.apply_preset("normal"));
+    assert_eq!(&vrad.get_args()[2..], &[VradArg::Both]);
+    assert!(!vrad.apply_preset("ultra"));
+
+    // Going back to `normal` from any preset restores the default arguments.
+    for presets in [["fast", "normal"], ["final", "normal"]] {
+        let mut vrad = Vrad::default();
+        for preset in presets {
+            assert!(vrad.apply_preset(preset));
+        }
+        assert_eq!(vrad.get_args(), Vrad::default().get_args(), "{:?}", presets);
+    }
+    let mut vrad = Vrad::preset("fast").unwrap();
+    for preset in ["final", "fast", "normal"] {
+        vrad.apply_preset(preset);
+    }
+    assert_eq!(vrad.get_args(), Vrad::default().get_args());
+
+    let mut compiler = CompilerEnum::Vrad(Vrad::default());
+    assert_eq!(compiler.presets().len(), 3);
+    assert!(compiler.apply_preset("final"));
+    assert!(compiler.validate().is_ok());
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "dynamic")]
+
+use valve_compilers::{Compiler, CompilerArg, CompilerContext, ParseArgError};
+use valve_compilers::dynamic::{CompilerDefinition, DefinitionError, DynamicCompiler, DynamicValue};
+use std::path::{Path, PathBuf};
+
+const HAMMER_PLUS_PLUS_VRAD: &str = r#"
+name = "VRAD++"
+description = "Community lighting compiler."
+working_dir = "$binDir"
+base_arguments = "-game $gameDir"
+
+[[parameters]]
+name = "Map File"
+description = "The BSP file to light."
+argument = ""
+value_type = "path"
+default_value = "$mapPath"
+is_default = true
+
+[[parameters]]
+name = "Threads"
+description = "Number of worker threads."
+argument = "-threads"
+value_type = "integer"
+constraints = { min_value = 1, max_value = 64 }
+
+[[parameters]]
+name = "Preview"
+description = "Fast preview lighting."
+argument = "-preview"
+value_type = "flag"
+conflicts_with = ["-threads"]
+"#;
+
+/// Test 6.1: Verifies that a built-in config loaded at runtime behaves like the generated compiler.
+#[test]
+fn test_dynamic_matches_generated_compiler() {
+    let config_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("compiler_configs/vbsp.toml");
+    let definition = CompilerDefinition::load(config_path).unwrap();
+
+    let mut dynamic = DynamicCompiler::with_defaults(definition);
+    dynamic.add_arg(dynamic.parse_arg("-micro 0.5").unwrap());
+    let mut generated = valve_compilers::vbsp::Vbsp::default();
+    generated.add_arg(valve_compilers::vbsp::VbspArg::MicroVolumeTest(0.5));
+
+    assert_eq!(dynamic.name(), generated.name());
+    assert_eq!(dynamic.build_args(), generated.build_args());
+    assert_eq!(dynamic.get_args()[2].value(), &DynamicValue::Float(0.5));
+    assert_eq!(dynamic.validate_for_game(730), generated.validate_for_game(730));
+
+    let all = CompilerDefinition::load_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("compiler_configs")).unwrap();
+    assert!(all.iter().any(|d| d.name == "VBSP Info"));
+}
+
+/// Test 6.2: Verifies a user-supplied tool config: base arguments, constraints and command building.
+#[test]
+fn test_dynamic_custom_tool() {
+    let definition = CompilerDefinition::from_toml_str(HAMMER_PLUS_PLUS_VRAD).unwrap();
+
+    let compiler = DynamicCompiler::from_command_line_with(definition, "-game $gameDir -threads 8 my_map.bsp").unwrap();
+    assert_eq!(compiler.build_args(), vec!["-game", "$gameDir", "-threads", "8", "my_map.bsp"]);
+    assert_eq!(compiler.get_args()[0].name(), "Threads");
+
+    let context = CompilerContext::new(Some(PathBuf::from("/bin")), Some(PathBuf::from("/game")), None, None);
+    let command = compiler.build_command(&context, None);
+    assert_eq!(command.args, vec!["-game", "/game", "-threads", "8", "my_map.bsp"]);
+    assert_eq!(command.working_dir, PathBuf::from("/bin"));
+
+    let err = compiler.parse_arg("-threads 100").unwrap_err();
+    assert!(matches!(err, ParseArgError::OutOfRange { argument: "-threads", .. }));
+
+    let mut conflicting = DynamicCompiler::new(definition);
+    conflicting.add_arg(compiler.parse_arg("-preview").unwrap());
+    conflicting.add_arg(compiler.parse_arg("-threads 4").unwrap());
+    assert!(conflicting.validate().is_err());
+}
+
+/// Test 6.3: Verifies that broken configs are rejected with a `DefinitionError`.
+#[test]
+fn test_dynamic_invalid_definitions() {
+    assert!(matches!(CompilerDefinition::from_toml_str("name = 1"), Err(DefinitionError::Toml(_))));
+
+    let bad_reference = HAMMER_PLUS_PLUS_VRAD.replace(r#"conflicts_with = ["-threads"]"#, r#"requires = ["-nope"]"#);
+    let err = CompilerDefinition::from_toml_str(&bad_reference).unwrap_err();
+    assert!(matches!(err, DefinitionError::Invalid { parameter, .. } if parameter == "Preview"));
+
+    let unknown_game = HAMMER_PLUS_PLUS_VRAD.replace(r#"conflicts_with = ["-threads"]"#, r#"constraints = { compatible_games = ["tf2", "not a game"] }"#);
+    let err = CompilerDefinition::from_toml_str(&unknown_game).unwrap_err();
+    assert!(matches!(err, DefinitionError::Invalid { reason, .. } if reason == "unknown game 'not a game'"));
+
+    assert!(matches!(CompilerDefinition::load("/definitely/not/here.toml"), Err(DefinitionError::Io { .. })));
+}
+
+/// Test 6.4: Verifies that game names in constraints resolve through the `SourceGame` registry.
+#[test]
+fn test_dynamic_game_names() {
+    use valve_compilers::SourceGame;
+
+    let toml = HAMMER_PLUS_PLUS_VRAD.replace(
+        r#"conflicts_with = ["-threads"]"#,
+        r#"constraints = { compatible_games = ["TF2", "Counter-Strike: Source", 669270], incompatible_games = ["gmod"] }"#,
+    );
+    let definition = CompilerDefinition::from_toml_str(&toml).unwrap();
+    let preview = definition.find_parameter("-preview").unwrap();
+    assert_eq!(preview.compatible_games, Some(&[440, 240, 669270][..]));
+    assert_eq!(preview.incompatible_games, Some(&[4000][..]));
+
+    let compiler = DynamicCompiler::new(definition);
+    let arg = compiler.parse_arg("-preview").unwrap();
+    assert_eq!(arg.compatible_source_games(), Some(vec![SourceGame::Tf2, SourceGame::Css, SourceGame::Momentum]));
+    assert!(!arg.is_compatible_with(SourceGame::Gmod));
+}
+
+/// Test 6.5: Verifies that `[[game_defaults]]` behave the same at runtime as in generated code.
+#[test]
+fn test_dynamic_game_defaults() {
+    use valve_compilers::vrad::Vrad;
+
+    let config_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("compiler_configs/vrad.toml");
+    let definition = CompilerDefinition::load(config_path).unwrap();
+    for app_id in [440, 240, 730] {
+        let dynamic = DynamicCompiler::with_defaults_for_game(definition, app_id);
+        assert_eq!(dynamic.build_args(), Vrad::default_for_game(app_id).build_args());
+        assert_eq!(dynamic.build_args().last().map(String::as_str), Some("$mapPath"), "{}", app_id);
+    }
+
+    let bad_value = format!("{}\n[[game_defaults]]\ngames = [\"tf2\"]\nadd = [{{ argument = \"-threads\", value = \"128\" }}]\n", HAMMER_PLUS_PLUS_VRAD);
+    let err = CompilerDefinition::from_toml_str(&bad_value).unwrap_err();
+    assert!(matches!(err, DefinitionError::Invalid { parameter, .. } if parameter == "[[game_defaults]] #1"));
+
+    let good_value = bad_value.replace("\"128\"", "\"8\"");
+    let definition = CompilerDefinition::from_toml_str(&good_value).unwrap();
+    let compiler = DynamicCompiler::with_defaults_for_game(definition, 440);
+    assert_eq!(compiler.build_args(), vec!["-game", "$gameDir", "-threads", "8", "$mapPath"]);
+
+    // Float bounds are compared as `f32`, like the value itself.
+    let float_bound = format!(
+        r#"{}
+[[parameters]]
+name = "Scale"
+description = "Lightmap scale."
+argument = "-scale"
+value_type = "float"
+constraints = {{ max_value = 0.1 }}
+
+[[game_defaults]]
+games = ["tf2"]
+add = [{{ argument = "-scale", value = "0.1" }}]
+"#,
+        HAMMER_PLUS_PLUS_VRAD
+    );
+    let definition = CompilerDefinition::from_toml_str(&float_bound).unwrap();
+    let compiler = DynamicCompiler::with_defaults_for_game(definition, 440);
+    assert_eq!(compiler.build_args(), vec!["-game", "$gameDir", "-scale", "0.1", "$mapPath"]);
+}
+
+/// Test 6.6: Verifies that `[[presets]]` behave the same at runtime as in generated code.
+#[test]
+fn test_dynamic_presets() {
+    use valve_compilers::vrad::Vrad;
+
+    let config_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("compiler_configs/vrad.toml");
+    let definition = CompilerDefinition::load(config_path).unwrap();
+    assert_eq!(definition.presets, Vrad::default().presets());
+    for preset in definition.presets {
+        let dynamic = DynamicCompiler::with_preset(definition, preset.name).unwrap();
+        assert_eq!(dynamic.build_args(), Vrad::preset(preset.name).unwrap().build_args());
+        assert_eq!(dynamic.build_args().last().map(String::as_str), Some("$mapPath"), "{}", preset.name);
+    }
+    assert!(DynamicCompiler::with_preset(definition, "ultra").is_none());
+
+    let duplicate = format!("{}\n[[presets]]\nname = \"fast\"\n\n[[presets]]\nname = \"FAST\"\n", HAMMER_PLUS_PLUS_VRAD);
+    let err = CompilerDefinition::from_toml_str(&duplicate).unwrap_err();
+    assert!(matches!(err, DefinitionError::Invalid { parameter, .. } if parameter == "[[presets]] #2"));
+}
@@ -0,0 +1,104 @@
+#![cfg(unix)]
+
+use valve_compilers::{CommandInfo, ExecutionError, OutputLine};
+use std::path::PathBuf;
+
+fn shell_command(script: &str, working_dir: PathBuf) -> CommandInfo {
+    CommandInfo {
+        name: "SH",
+        compiler_path: PathBuf::from("sh"),
+        args: vec!["-c".to_string(), script.to_string()],
+        working_dir,
+        env: Vec::new(),
+    }
+}
+
+/// Test 4.1: Verifies that output lines from both streams are forwarded and the exit status is reported.
+#[test]
+fn test_run_streams_output_and_status() {
+    let command = shell_command("echo first; echo oops 1>&2; echo second; exit 3", PathBuf::new());
+
+    let mut lines = Vec::new();
+    let result = command.run(|line| lines.push(line)).expect("Failed to run sh");
+
+    assert_eq!(result.name, "SH");
+    assert!(!result.success());
+    assert_eq!(result.exit_code(), Some(3));
+
+    let stdout: Vec<&str> = lines.iter()
+        .filter(|l| matches!(l, OutputLine::Stdout(_)))
+        .map(OutputLine::text)
+        .collect();
+    assert_eq!(stdout, vec!["first", "second"]);
+    assert!(lines.contains(&OutputLine::Stderr("oops".to_string())));
+}
+
+/// Test 4.2: Verifies that the working directory is applied to the child process.
+#[test]
+fn test_run_uses_working_dir() {
+    let dir = std::env::temp_dir().canonicalize().unwrap();
+    let command = shell_command("pwd", dir.clone());
+
+    let mut lines = Vec::new();
+    let result = command.run(|line| lines.push(line)).unwrap();
+
+    assert!(result.success());
+    assert_eq!(lines, vec![OutputLine::Stdout(dir.display().to_string())]);
+}
+
+/// Test 4.3: Verifies that launch failures are mapped into `ExecutionError`.
+#[test]
+fn test_run_error_cases() {
+    let missing_exe = CommandInfo {
+        name: "VBSP",
+        compiler_path: PathBuf::from("/definitely/not/here/vbsp.exe"),
+        args: Vec::new(),
+        working_dir: PathBuf::new(),
+        env: Vec::new(),
+    };
+    let err = missing_exe.run(|_| {}).unwrap_err();
+    assert!(matches!(err, ExecutionError::ExecutableNotFound(p) if p == missing_exe.compiler_path));
+
+    let missing_wd = shell_command("true", PathBuf::from("/definitely/not/here"));
+    let err = missing_wd.run(|_| {}).unwrap_err();
+    assert!(matches!(err, ExecutionError::WorkingDirNotFound(_)));
+}
+
+/// Test 4.4: Verifies that a Wine wrapper launches the compiler through the wrapper with
+/// `WINEPREFIX` set and `$gameDir`/`$mapPath` rendered as `Z:\` paths.
+#[test]
+fn test_run_through_wine_wrapper() {
+    use std::os::unix::fs::PermissionsExt;
+    use valve_compilers::{Compiler, CompilerContext, Wrapper};
+    use valve_compilers::vbsp::Vbsp;
+
+    let dir = std::env::temp_dir().join(format!("valve_compilers_wine_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let stub = dir.join("wine-stub");
+    std::fs::write(&stub, "#!/bin/sh\necho \"prefix=$WINEPREFIX\"\nprintf '%s\\n' \"$@\"\n").unwrap();
+    std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let wrapper = Wrapper::Wine { executable: stub.clone(), prefix: Some(PathBuf::from("/opt/prefix")) };
+    let mut context = CompilerContext::new(
+        Some(dir.clone()),
+        Some(PathBuf::from("/sdk/cstrike")),
+        Some(PathBuf::from("/maps/test.vmf")),
+        None,
+    );
+    wrapper.prepare_context(&mut context);
+
+    let command = Vbsp::default().build_command(&context, Some(PathBuf::from("/sdk/bin/vbsp.exe")));
+    assert_eq!(command.working_dir, dir);
+    let wrapped = wrapper.wrap(&command);
+    assert_eq!(wrapped.compiler_path, stub);
+
+    let mut lines = Vec::new();
+    let result = wrapped.run(|line| lines.push(line.text().to_string())).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.success());
+    assert_eq!(lines[0], "prefix=/opt/prefix");
+    assert_eq!(lines[1], "/sdk/bin/vbsp.exe");
+    assert!(lines.contains(&"Z:\\sdk\\cstrike".to_string()));
+    assert!(lines.contains(&"Z:\\maps\\test.vmf".to_string()));
+}
@@ -0,0 +1,256 @@
+use valve_compilers::cmdseq::{self, CmdSeqError, SequenceCommand, SequenceStep, SpecialCommand, UnsupportedReason};
+use valve_compilers::compilepal::{CompilePalError, CompilePalPreset, UnmappedReason};
+use valve_compilers::{Compiler, CompilerContext, CompilerEnum, ParseArgError};
+use std::fs;
+use std::path::PathBuf;
+
+/// Appends `value` as a zero-padded fixed-size string field.
+fn field(data: &mut Vec<u8>, value: &str, len: usize) {
+    data.extend_from_slice(value.as_bytes());
+    data.resize(data.len() + len - value.len(), 0);
+}
+
+/// A command as `(special, run, parameters)`.
+type RawCommand<'a> = (i32, &'a str, &'a str);
+
+/// Builds a `CmdSeq.wc` the way Hammer writes it.
+fn cmdseq_bytes(version: f32, sequences: &[(&str, &[RawCommand])]) -> Vec<u8> {
+    let mut data = cmdseq::SIGNATURE.to_vec();
+    data.extend_from_slice(&version.to_le_bytes());
+    data.extend_from_slice(&(sequences.len() as u32).to_le_bytes());
+    for (name, commands) in sequences {
+        field(&mut data, name, 128);
+        data.extend_from_slice(&(commands.len() as u32).to_le_bytes());
+        for (special, run, parameters) in *commands {
+            data.extend_from_slice(&1u32.to_le_bytes());
+            data.extend_from_slice(&special.to_le_bytes());
+            field(&mut data, run, 260);
+            field(&mut data, parameters, 260);
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(&0u32.to_le_bytes());
+            field(&mut data, "", 260);
+            if version >= 0.2 {
+                data.extend_from_slice(&1u32.to_le_bytes());
+                data.extend_from_slice(&0u32.to_le_bytes());
+            }
+        }
+    }
+    data
+}
+
+/// Test 8.1: Verifies that Hammer macros map onto placeholders and back.
+#[test]
+fn test_hammer_macro_translation() {
+    assert_eq!(cmdseq::from_hammer_macros(r"$path\$file.bsp"), r"$mapDir\$mapName.bsp");
+    assert_eq!(cmdseq::from_hammer_macros("-game $GameDir $bspdir"), "-game $gameDir $outDir");
+    assert_eq!(cmdseq::from_hammer_macros("$light_exe costs $5"), "$light_exe costs $5");
+    assert_eq!(cmdseq::to_hammer_macros(r"$mapDir\$mapName.bsp"), r"$path\$file.bsp");
+    // This crate's `$path` is the full map path, Hammer's is the map directory.
+    assert_eq!(cmdseq::to_hammer_macros("$path"), r"$path\$file.$ext");
+}
+
+/// Test 8.2: Verifies reading a `CmdSeq.wc` into compilers, programs and built-in commands.
+#[test]
+fn test_cmdseq_parsing() {
+    let data = cmdseq_bytes(0.2, &[
+        ("[Default]", &[
+            (0, "$bsp_exe", r"-game $gamedir $path\$file"),
+            (0, "$light_exe", r"-both -bounce 2 -game $gamedir $path\$file"),
+            (0x101, "", r"$path\$file.bsp $bspdir\$file.bsp"),
+            (0, r"C:\tools\notify.exe", r#""compile done" $file"#),
+            (0, "$vis_exe", r"-game $gamedir -no_such_switch $path\$file"),
+        ]),
+        ("Empty", &[]),
+    ]);
+    let sequences = cmdseq::parse(&data).unwrap();
+    assert_eq!(sequences.len(), 2);
+    assert_eq!(sequences[0].name, "[Default]");
+    assert!(sequences[1].commands.is_empty());
+
+    let commands = &sequences[0].commands;
+    let SequenceStep::Compiler(CompilerEnum::Vrad(vrad)) = &commands[1].step else { panic!("expected VRAD") };
+    assert_eq!(vrad.selected_args.len(), 4);
+    assert_eq!(commands[1].parameters(), r"-both -bounce 2 -game $gamedir $path\$file");
+    assert!(matches!(commands[2].step, SequenceStep::Special { command: SpecialCommand::CopyFile, .. }));
+    assert!(matches!(&commands[3].step, SequenceStep::Program { parameters } if parameters == r#""compile done" $file"#));
+    // Arguments the compiler does not know keep the step as a plain program.
+    assert!(matches!(commands[4].step, SequenceStep::Program { .. }));
+
+    // Writing the file back produces the same bytes.
+    assert_eq!(cmdseq::to_bytes(&sequences).unwrap(), data);
+
+    // Version 0.1 files lack the process window options.
+    let old = cmdseq::parse(&cmdseq_bytes(0.1, &[("Old", &[(0, "$bsp_exe", "$path\\$file")])])).unwrap();
+    assert!(old[0].commands[0].use_process_window && !old[0].commands[0].no_wait);
+
+    assert!(matches!(cmdseq::parse(b"Worldcraft Command Sequences v2.0\0\0\0\0"), Err(CmdSeqError::InvalidSignature)));
+    assert!(matches!(cmdseq::parse(&cmdseq_bytes(0.3, &[])), Err(CmdSeqError::UnsupportedVersion(_))));
+    assert!(matches!(cmdseq::parse(&data[..data.len() - 1]), Err(CmdSeqError::UnexpectedEof)));
+}
+
+/// Test 8.3: Verifies that sequence steps become commands through a `CompilerContext`.
+#[test]
+fn test_cmdseq_build_commands() {
+    let data = cmdseq_bytes(0.2, &[("Fast", &[
+        (0, "$bsp_exe", r"-game $gamedir $path\$file"),
+        (0x100, "", "$path"),
+        (0, "/usr/bin/notify-send", "$file.bsp"),
+    ])]);
+    let mut sequences = cmdseq::parse(&data).unwrap();
+    let context = CompilerContext::new(
+        Some(PathBuf::from("/sdk/bin")),
+        Some(PathBuf::from("/sdk/tf")),
+        Some(PathBuf::from("/maps/ctf_test.vmf")),
+        None,
+    );
+
+    let (commands, unsupported) = sequences[0].build_commands(&context);
+    assert_eq!(commands.len(), 2);
+    assert_eq!(unsupported.len(), 1);
+    assert_eq!((unsupported[0].index, &unsupported[0].reason), (1, &UnsupportedReason::Special(SpecialCommand::ChangeDir)));
+    assert_eq!(commands[0].name, "VBSP");
+    // The `\` after Hammer's `$path` follows the context's path style.
+    let map = format!("/maps{}ctf_test", std::path::MAIN_SEPARATOR);
+    assert_eq!(commands[0].args, vec!["-game", "/sdk/tf", map.as_str()]);
+    assert_eq!(commands[1].compiler_path, PathBuf::from("/usr/bin/notify-send"));
+    assert_eq!(commands[1].args, vec!["ctf_test.bsp"]);
+
+    // Disabled steps are skipped.
+    sequences[0].commands[0].enabled = false;
+    assert_eq!(sequences[0].build_commands(&context).0.len(), 1);
+
+    let step = SequenceCommand::new("vrad.exe", "-fast $path\\$file");
+    assert!(matches!(step.step, SequenceStep::Compiler(CompilerEnum::Vrad(_))));
+}
+
+/// Test 8.4: Verifies the Hammer++ text format, conversion into pipeline stages and the
+/// report of steps the pipeline cannot run like Hammer.
+#[test]
+fn test_cmdseq_text_format() {
+    let text = r#"
+    "Command Sequences"
+    {
+        "Full Compile"
+        {
+            "1" { "enable" "1" "specialcmd" "0" "run" "$bsp_exe" "params" "-game $gamedir $path\$file" }
+            "2" { "enable" "0" "specialcmd" "0" "run" "$vis_exe" "params" "-fast -game $gamedir $path\$file" }
+            "3" { "run" "$exedir\vrad.exe" "params" "-final -game $gamedir $path\$file" "nowait" "1" }
+            "4" { "specialcmd" "257" "params" "$path\$file.bsp $bspdir\$file.bsp" }
+            "5" { "run" "C:\tools\lightmap_tool.exe" "params" "$path\$file.bsp -quality high" }
+        }
+    }
+    "#;
+    let sequences = cmdseq::parse_text(text).unwrap();
+    let commands = &sequences[0].commands;
+    assert_eq!(sequences[0].name, "Full Compile");
+    assert_eq!(commands.len(), 5);
+    assert!(!commands[1].enabled && commands[2].no_wait);
+    assert!(matches!(commands[2].step, SequenceStep::Compiler(CompilerEnum::Vrad(_))));
+    assert!(matches!(commands[3].step, SequenceStep::Special { command: SpecialCommand::CopyFile, .. }));
+
+    // Disabled steps and built-in commands have no stage; unknown programs become custom stages.
+    let (pipeline, unsupported) = sequences[0].to_pipeline();
+    let names: Vec<&str> = pipeline.stages.iter().map(|stage| stage.compiler.name()).collect();
+    assert_eq!(names, ["VBSP", "VRAD", "Custom Command"]);
+    assert_eq!(pipeline.stages[1].executable, Some(PathBuf::from(r"$binDir\vrad.exe")));
+    let CompilerEnum::Custom(custom) = &pipeline.stages[2].compiler else { panic!("expected a custom stage") };
+    assert_eq!(custom.program, r"C:\tools\lightmap_tool.exe");
+    assert_eq!(custom.build_args(), [r"$mapDir\$mapName.bsp", "-quality", "high"]);
+
+    // Options the pipeline cannot honour are reported with the built-in commands.
+    let reasons: Vec<(usize, &UnsupportedReason)> = unsupported.iter().map(|step| (step.index, &step.reason)).collect();
+    assert_eq!(reasons, [(2, &UnsupportedReason::NoWait), (3, &UnsupportedReason::Special(SpecialCommand::CopyFile))]);
+    assert_eq!(unsupported[1].to_string(), "step 4 '': built-in command CopyFile is not run");
+    let mut checked = sequences.clone();
+    checked[0].commands[0].ensure_check = true;
+    checked[0].commands[0].ensure_file = r"$path\$file.bsp".to_string();
+    assert_eq!(
+        checked[0].to_pipeline().1[0].reason,
+        UnsupportedReason::EnsureCheck { file: r"$path\$file.bsp".to_string() }
+    );
+
+    // Text output reads back into the same steps.
+    let written = cmdseq::to_text(&sequences).unwrap();
+    assert!(written.contains(r#""params"	"-game $gamedir $path\$file""#));
+    let reread = cmdseq::parse_text(&written).unwrap();
+    let parameters = |sequences: &[cmdseq::CommandSequence]| -> Vec<String> {
+        sequences[0].commands.iter().map(SequenceCommand::parameters).collect()
+    };
+    assert_eq!(parameters(&reread), parameters(&sequences));
+
+    let mut quoted = sequences.clone();
+    quoted[0].commands[4].step = SequenceStep::Program { parameters: r#""C:\my maps\x.bsp""#.to_string() };
+    assert!(matches!(cmdseq::to_text(&quoted), Err(CmdSeqError::QuoteInText { field: "parameters", .. })));
+
+    assert!(matches!(cmdseq::parse_text("\"key\" \"value\""), Err(CmdSeqError::InvalidSignature)));
+    assert!(matches!(cmdseq::parse_text("\"Command Sequences\" {"), Err(CmdSeqError::Text(_))));
+}
+
+/// Test 8.5: Verifies importing a CompilePal preset folder and the report of unmapped lines.
+#[test]
+fn test_compilepal_import() {
+    let dir = std::env::temp_dir().join(format!("valve_compilers_compilepal_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let preset_dir = dir.join("Final");
+    fs::create_dir_all(&preset_dir).unwrap();
+    fs::write(preset_dir.join("vrad.csv"), "-final,\r\n-textureshadows,\r\n-bounce,8\r\n\r\n-bounce,many\r\n-no_such_switch,\r\n").unwrap();
+    fs::write(preset_dir.join("vbsp.csv"), "").unwrap();
+    fs::write(preset_dir.join("cubemaps.csv"), "-hdr,\n").unwrap();
+    fs::write(preset_dir.join("notes.txt"), "not a preset file").unwrap();
+
+    let preset = CompilePalPreset::load(&preset_dir).unwrap();
+    assert_eq!(preset.name, "Final");
+    assert!(preset.vvis.is_none() && preset.bspzip.is_none());
+    assert_eq!(preset.vbsp.as_ref().unwrap().build_args(), ["-game", "$gameDir", "$mapPath"]);
+    assert_eq!(
+        preset.vrad.as_ref().unwrap().build_args(),
+        ["-game", "$gameDir", "-final", "-TextureShadows", "-bounce", "8", "$mapPath"]
+    );
+
+    // The report lists every line that was not imported, in file order.
+    let report: Vec<(&str, usize)> = preset
+        .report
+        .iter()
+        .map(|line| (line.file.file_name().unwrap().to_str().unwrap(), line.line))
+        .collect();
+    assert_eq!(report, [("cubemaps.csv", 1), ("vrad.csv", 5), ("vrad.csv", 6)]);
+    assert_eq!(preset.report[0].reason, UnmappedReason::UnknownCompiler);
+    assert!(matches!(preset.report[1].reason, UnmappedReason::Parse(ParseArgError::InvalidValue { argument: "-bounce", .. })));
+    assert!(matches!(&preset.report[2].reason, UnmappedReason::Parse(ParseArgError::UnknownArgument(_))));
+    assert_eq!(preset.report[2].text, "-no_such_switch,");
+
+    let pipeline = preset.to_pipeline();
+    let names: Vec<&str> = pipeline.stages.iter().map(|stage| stage.compiler.name()).collect();
+    assert_eq!(names, ["VBSP", "VRAD"]);
+    // Imported switches are passed before the map, which the compilers expect last.
+    for stage in &pipeline.stages {
+        assert_eq!(stage.compiler.build_args().last().map(String::as_str), Some("$mapPath"), "{}", stage.compiler.name());
+    }
+
+    assert!(matches!(CompilePalPreset::load(dir.join("Missing")), Err(CompilePalError::Io { .. })));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Test 8.6: Verifies that quoted Hammer macros survive a round trip and are quoted again
+/// when the arguments of a step change.
+#[test]
+fn test_cmdseq_quoted_macros() {
+    use valve_compilers::vbsp::VbspArg;
+
+    let parameters = r#"-game "$gamedir" "$path\$file""#;
+    let data = cmdseq_bytes(0.2, &[("Quoted", &[(0, "$bsp_exe", parameters)])]);
+    let mut sequences = cmdseq::parse(&data).unwrap();
+    assert!(matches!(sequences[0].commands[0].step, SequenceStep::Compiler(CompilerEnum::Vbsp(_))));
+    assert_eq!(sequences[0].commands[0].parameters(), parameters);
+    assert_eq!(cmdseq::to_bytes(&sequences).unwrap(), data);
+
+    let SequenceStep::Compiler(CompilerEnum::Vbsp(vbsp)) = &mut sequences[0].commands[0].step else { unreachable!() };
+    vbsp.add_arg(VbspArg::Verbose);
+    assert_eq!(sequences[0].commands[0].parameters(), r#"-game "$gamedir" -verbose "$path\$file""#);
+
+    // Steps written without quotes stay unquoted.
+    let mut step = SequenceCommand::new("$bsp_exe", r"-game $gamedir $path\$file");
+    let SequenceStep::Compiler(CompilerEnum::Vbsp(vbsp)) = &mut step.step else { unreachable!() };
+    vbsp.add_arg(VbspArg::Verbose);
+    assert_eq!(step.parameters(), r"-game $gamedir -verbose $path\$file");
+}
@@ -1,7 +1,7 @@
 use valve_compilers::ParseArgError;
 use valve_compilers::vbsp::VbspArg;
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Test 2.1: Test successful parsing of all argument types.
 #[test]
@@ -16,7 +16,7 @@ fn test_parsing_success_cases() {
     assert!(matches!(arg_int, VbspArg::StaticPropCombineMinInstances(42)));
     // Path (from a different compiler for variety)
     let bspzip_arg = valve_compilers::bspzip::BspzipArg::try_from("-addlist /my/path/list.txt").unwrap();
-    assert!(matches!(bspzip_arg, valve_compilers::bspzip::BspzipArg::PackFileList(p) if p == PathBuf::from("/my/path/list.txt")));
+    assert!(matches!(bspzip_arg, valve_compilers::bspzip::BspzipArg::PackFileList(p) if p.as_path() == Path::new("/my/path/list.txt")));
 }
 
 /// Test 2.2: Test all defined parsing error conditions.
@@ -46,3 +46,158 @@ fn test_parsing_from_owned_string() {
     let arg = VbspArg::try_from(owned_string).unwrap();
     assert!(matches!(arg, VbspArg::NoDetail));
 }
+
+/// Test 2.4: Test that numeric range constraints are enforced and exposed as metadata.
+#[test]
+fn test_parsing_range_constraints() {
+    use valve_compilers::{CompilerArg, ValueRange};
+    use valve_compilers::vrad::VradArg;
+
+    let range = VradArg::LuxelDensity(0.5).value_range().unwrap();
+    assert_eq!(range, ValueRange { min: None, max: Some(1.0) });
+    assert!(range.contains(1.0));
+    assert!(!range.contains(1.5));
+
+    // Values on the boundary are accepted.
+    assert!(matches!(VradArg::try_from("-luxeldensity 1").unwrap(), VradArg::LuxelDensity(v) if v == 1.0));
+    assert!(matches!(VradArg::try_from("-luxeldensity 0.25").unwrap(), VradArg::LuxelDensity(_)));
+
+    let err = VradArg::try_from("-luxeldensity 2.5").unwrap_err();
+    assert_eq!(err, ParseArgError::OutOfRange { argument: "-luxeldensity", value: "2.5".to_string(), range });
+
+    // NaN is in no range, as in `ValueRange::contains`.
+    assert!(!range.contains(f64::NAN));
+    let err = VradArg::try_from("-luxeldensity NaN").unwrap_err();
+    assert_eq!(err, ParseArgError::OutOfRange { argument: "-luxeldensity", value: "NaN".to_string(), range });
+
+    // Unconstrained numeric arguments report no range.
+    assert_eq!(VradArg::Bounces(100).value_range(), None);
+}
+
+/// Test 2.5: Test Windows-style tokenization of command lines.
+#[test]
+fn test_split_windows_command_line() {
+    use valve_compilers::command_line::split_windows;
+
+    assert_eq!(split_windows(r#"-game "C:\x y\csgo" -micro 0.5"#), vec!["-game", r"C:\x y\csgo", "-micro", "0.5"]);
+    assert_eq!(split_windows(r#"  a   "" b  "#), vec!["a", "", "b"]);
+    assert_eq!(split_windows(r#""C:\dir with space\\" next"#), vec![r"C:\dir with space\", "next"]);
+    assert_eq!(split_windows(r#"a\\\"b "c""d" e\f"#), vec![r#"a\"b"#, r#"c"d"#, r"e\f"]);
+}
+
+/// Test 2.6: Test parsing a full command line back into a configured compiler.
+#[test]
+fn test_parsing_full_command_line() {
+    use valve_compilers::Compiler;
+    use valve_compilers::vbsp::Vbsp;
+
+    let compiler = Vbsp::from_command_line(r#"-game "C:\x y\csgo" -micro 0.5 -verbose map.vmf"#).unwrap();
+    assert_eq!(compiler.get_args(), &[
+        VbspArg::GameDirectory(PathBuf::from(r"C:\x y\csgo")),
+        VbspArg::MicroVolumeTest(0.5),
+        VbspArg::Verbose,
+        VbspArg::MapFile(PathBuf::from("map.vmf")),
+    ]);
+    assert_eq!(Vbsp::from_args(&compiler.build_args()).unwrap().get_args(), compiler.get_args());
+
+    // Multi-word switches and the catch-all positional string of the GAME config.
+    let game = valve_compilers::game::Game::from_command_line("-novid +sv_cheats 1 -somethingnew").unwrap();
+    assert_eq!(game.build_args(), vec!["-novid", "+sv_cheats 1", "-somethingnew"]);
+    assert_eq!(valve_compilers::game::Game::from_args(&game.build_args()).unwrap().get_args(), game.get_args());
+
+    // Errors from the single-argument parser are propagated.
+    assert_eq!(Vbsp::from_command_line("-micro").unwrap_err(), ParseArgError::MissingValue("-micro"));
+    assert_eq!(
+        Vbsp::from_command_line("map.vmf -bogus").unwrap_err(),
+        ParseArgError::UnknownArgument("-bogus".to_string())
+    );
+
+    // A second map has no positional left to take it.
+    assert_eq!(
+        Vbsp::from_command_line("-game x a.vmf b.vmf").unwrap_err(),
+        ParseArgError::UnknownArgument("b.vmf".to_string())
+    );
+    // A switch is not the value of a path or number, but a negative number is.
+    assert_eq!(Vbsp::from_command_line("-game -verbose a.vmf").unwrap_err(), ParseArgError::MissingValue("-game"));
+    assert_eq!(Vbsp::from_command_line("-micro -verbose").unwrap_err(), ParseArgError::MissingValue("-micro"));
+    assert_eq!(Vbsp::from_command_line("-micro -0.5 a.vmf").unwrap().get_args()[0], VbspArg::MicroVolumeTest(-0.5));
+}
+
+/// Test 2.7: Test that joined arguments split back into exactly the same list.
+#[test]
+fn test_join_windows_command_line() {
+    use valve_compilers::command_line::{join_windows, split_windows};
+
+    assert_eq!(join_windows(&["-game", r"C:\x y\csgo", r"C:\maps\"]), r#"-game "C:\x y\csgo" C:\maps\"#);
+    assert_eq!(join_windows(&[r"C:\dir with space\", ""]), r#""C:\dir with space\\" """#);
+
+    let cases: &[&[&str]] = &[
+        &[r#"a\"b"#, r#"c"d"#, r"e\f"],
+        &[r#"say "hi"\"#, "\ttab", r"\\server\share\"],
+        &["", "", "x"],
+    ];
+    for args in cases {
+        assert_eq!(split_windows(&join_windows(args)), *args);
+    }
+}
+
+/// Test 2.8: Test that rendered command lines split back into the same program and arguments in every shell,
+/// and into the same compiler.
+#[test]
+fn test_command_line_round_trip() {
+    use valve_compilers::command_line::split_command_line;
+    use valve_compilers::{CommandInfo, ShellKind};
+
+    let command = |program: &str, args: &[&str]| CommandInfo {
+        name: "VBSP",
+        compiler_path: PathBuf::from(program),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        working_dir: PathBuf::new(),
+        env: Vec::new(),
+    };
+
+    let paths = command(r"C:\Program Files (x86)\bin\vbsp.exe", &["-game", r"C:\My Game\hl2", r"D:\maps\"]);
+    assert_eq!(
+        paths.to_command_line(ShellKind::Cmd),
+        r#""C:\Program Files (x86)\bin\vbsp.exe" -game "C:\My Game\hl2" D:\maps\"#
+    );
+    assert_eq!(
+        paths.to_command_line(ShellKind::PowerShell),
+        r"& 'C:\Program Files (x86)\bin\vbsp.exe' -game 'C:\My Game\hl2' D:\maps\"
+    );
+    assert_eq!(
+        command("/sdk/bin/vbsp", &["-game", "/games/my game/tf", "it's"]).to_command_line(ShellKind::Posix),
+        r"/sdk/bin/vbsp -game '/games/my game/tf' 'it'\''s'"
+    );
+
+    let args = [
+        "", "a b", "it's", r#"say "a&b""#, r"C:\dir\", r#"end\\""#, "100%", "$HOME", "*", "^caret",
+        "(x|y)", "tab\there", "new\nline", "\u{2018}typographic\u{2019}", "-vproject:x", "-a.b",
+    ];
+    for program in ["vbsp", r"C:\Program Files (x86)\bin\vbsp.exe", "/opt/my tools/vbsp"] {
+        let info = command(program, &args);
+        let expected: Vec<&str> = std::iter::once(program).chain(args).collect();
+        for shell in [ShellKind::Cmd, ShellKind::PowerShell, ShellKind::Posix] {
+            assert_eq!(split_command_line(&info.to_command_line(shell), shell), expected, "{:?}", shell);
+        }
+    }
+
+    // A rendered compiler command parses back into the same compiler.
+    use valve_compilers::vbsp::Vbsp;
+    use valve_compilers::{Compiler, CompilerContext};
+    let mut vbsp = Vbsp::new();
+    vbsp.add_arg(VbspArg::GameDirectory(PathBuf::from(r"C:\Program Files (x86)\Steam\common\My Game\")));
+    vbsp.add_arg(VbspArg::MicroVolumeTest(-0.5));
+    vbsp.add_arg(VbspArg::MapFile(PathBuf::from(r"D:\maps (old)\a&b.vmf")));
+    let info = vbsp.build_command(&CompilerContext::default(), Some(PathBuf::from("vbsp.exe")));
+    let line = info.to_command_line(ShellKind::Cmd);
+    let parsed = Vbsp::from_command_line(line.strip_prefix("vbsp.exe ").unwrap()).unwrap();
+    assert_eq!(parsed.get_args(), vbsp.get_args());
+    assert_eq!(Vbsp::from_args(&split_command_line(&line, ShellKind::Cmd)[1..]).unwrap().get_args(), vbsp.get_args());
+
+    // The parsers follow `CommandLineToArgvW` and `sh`, also for lines written by hand.
+    use valve_compilers::command_line::{split_posix, split_windows_command};
+    assert_eq!(split_windows_command(r#"  "C:\a b\x.exe"  -game "C:\x y\" "#), [r"C:\a b\x.exe", "-game", r#"C:\x y" "#]);
+    assert_eq!(split_posix(r#"a\ b "x\$y\z" 'q\' \
+c"#), ["a b", r"x$y\z", r"q\", "c"]);
+}
@@ -0,0 +1,77 @@
+#![cfg(unix)]
+
+use valve_compilers::{CompilerContext, Pipeline, PipelineStage, StageOutcome};
+use valve_compilers::bspzip::Bspzip;
+use valve_compilers::game::Game;
+use valve_compilers::vbsp::Vbsp;
+use valve_compilers::vrad::Vrad;
+use valve_compilers::vvis::Vvis;
+
+/// Test 5.1: Verifies that the pipeline runs every stage in order when all of them succeed.
+#[test]
+fn test_pipeline_all_stages_succeed() {
+    let mut pipeline = Pipeline::new();
+    pipeline.add_stage(PipelineStage::new(Vbsp::default()).with_executable("true"));
+    pipeline.add_stage(PipelineStage::new(Vvis::default()).with_executable("true"));
+    pipeline.add_stage(PipelineStage::new(Vrad::default()).with_executable("true"));
+
+    let report = pipeline.run(&CompilerContext::default(), |_, _| {});
+
+    assert!(report.success());
+    let names: Vec<&str> = report.stages.iter().map(|s| s.name).collect();
+    assert_eq!(names, vec!["VBSP", "VVIS", "VRAD"]);
+}
+
+/// Test 5.2: Verifies stop-on-failure, per-stage "continue on error" and skipped stages.
+#[test]
+fn test_pipeline_failure_handling() {
+    let mut pipeline = Pipeline::new();
+    pipeline.add_stage(PipelineStage::new(Vbsp::default()).with_executable("true"));
+    pipeline.add_stage(PipelineStage::new(Vvis::default()).with_executable("false").with_continue_on_error(true));
+    pipeline.add_stage(PipelineStage::new(Vrad::default()).with_executable("false"));
+    pipeline.add_stage(PipelineStage::new(Bspzip::default()).with_executable("true"));
+    pipeline.add_stage(PipelineStage::new(Game::default()).with_executable("true"));
+
+    let report = pipeline.run(&CompilerContext::default(), |_, _| {});
+
+    assert!(!report.success());
+    assert!(matches!(report.stages[0].outcome, StageOutcome::Succeeded(_)));
+    assert!(matches!(report.stages[1].outcome, StageOutcome::Failed(_)));
+    assert!(matches!(report.stages[2].outcome, StageOutcome::Failed(_)));
+    assert!(matches!(report.stages[3].outcome, StageOutcome::Skipped));
+    assert!(matches!(report.stages[4].outcome, StageOutcome::Skipped));
+    assert_eq!(report.first_failure().unwrap().name, "VVIS");
+}
+
+/// Test 5.3: Verifies that launch errors are reported per stage and halt the pipeline.
+#[test]
+fn test_pipeline_launch_error() {
+    let mut pipeline = Pipeline::new();
+    pipeline.add_stage(PipelineStage::new(Vbsp::default()).with_executable("/definitely/not/here/vbsp"));
+    pipeline.add_stage(PipelineStage::new(Vvis::default()).with_executable("true"));
+
+    let report = pipeline.run(&CompilerContext::default(), |_, _| {});
+
+    assert!(matches!(report.stages[0].outcome, StageOutcome::Error(_)));
+    assert!(matches!(report.stages[1].outcome, StageOutcome::Skipped));
+}
+
+/// Test 5.4: Verifies that custom command stages run arbitrary programs with placeholders.
+#[test]
+fn test_pipeline_custom_stage() {
+    use std::path::PathBuf;
+    use valve_compilers::custom::Custom;
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add_stage(PipelineStage::new(Custom::with_program("sh", ["-c", "echo $0", "$mapName"])));
+    pipeline.add_stage(PipelineStage::new(Custom::with_program("sh", ["-c", "exit 3"])));
+
+    let context = CompilerContext::new(None, None, Some(PathBuf::from("/tmp/ctf_test.vmf")), None);
+    let mut output = Vec::new();
+    let report = pipeline.run(&context, |name, line| output.push((name, line.text().to_string())));
+
+    assert_eq!(report.stages[0].name, "Custom Command");
+    assert!(report.stages[0].outcome.is_success());
+    assert_eq!(output, vec![("Custom Command", "ctf_test".to_string())]);
+    assert!(matches!(&report.stages[1].outcome, StageOutcome::Failed(result) if result.status.code() == Some(3)));
+}
@@ -0,0 +1,117 @@
+use valve_compilers::command_line::{self, ShellKind};
+use valve_compilers::vbsp::{Vbsp, VbspArg};
+use valve_compilers::{script, CommandInfo, Compiler, CompilerContext};
+use std::path::PathBuf;
+
+/// Builds the VBSP command of Test 3.6, whose paths contain spaces and parentheses.
+fn vbsp_command() -> CommandInfo {
+    let mut compiler = Vbsp::new();
+    compiler.add_arg(VbspArg::GameDirectory(PathBuf::from(r"C:\Program Files (x86)\Steam\steamapps\common\MyGame")));
+    compiler.add_arg(VbspArg::MapFile(PathBuf::from(r"D:\Maps For Game\level_01.vmf")));
+    let mut command = compiler.build_command(&CompilerContext::default(), Some(PathBuf::from(r"C:\SDK Tools\bin\vbsp.exe")));
+    command.working_dir = PathBuf::from(r"D:\Maps For Game");
+    command
+}
+
+/// Test 9.1: Verifies per-shell quoting of single arguments.
+#[test]
+fn test_shell_quoting() {
+    assert_eq!(command_line::quote("-game", ShellKind::Cmd), "-game");
+    assert_eq!(command_line::quote(r"C:\x (y)\", ShellKind::Cmd), r#""C:\x (y)\\""#);
+    assert_eq!(command_line::quote("a&b", ShellKind::Cmd), r#""a&b""#);
+    // `cmd.exe` sees the escaped quote as the end of the quoted part.
+    assert_eq!(command_line::quote(r#"say "a&b""#, ShellKind::Cmd), r#""say \"a^&b\"""#);
+
+    assert_eq!(command_line::quote(r"C:\maps\x.vmf", ShellKind::PowerShell), r"C:\maps\x.vmf");
+    assert_eq!(command_line::quote("-vproject:x", ShellKind::PowerShell), "'-vproject:x'");
+    assert_eq!(command_line::quote("it's $home", ShellKind::PowerShell), "'it''s $home'");
+    assert_eq!(command_line::quote("", ShellKind::PowerShell), "''");
+
+    assert_eq!(command_line::quote("/maps/ctf_test.vmf", ShellKind::Posix), "/maps/ctf_test.vmf");
+    assert_eq!(command_line::quote("it's $HOME", ShellKind::Posix), r"'it'\''s $HOME'");
+    assert_eq!(command_line::join(&["-bounce", "2", ""], ShellKind::Posix), "-bounce 2 ''");
+}
+
+/// Test 9.2: Verifies `.bat` and PowerShell scripts for arguments with spaces.
+#[test]
+fn test_windows_scripts() {
+    let mut command = vbsp_command();
+    command.env.push(("VPROJECT".to_string(), "100%".to_string()));
+
+    assert_eq!(
+        script::render(std::slice::from_ref(&command), ShellKind::Cmd),
+        [
+            "@echo off",
+            "",
+            "rem VBSP",
+            "setlocal",
+            r#"set "VPROJECT=100%%""#,
+            r#"cd /d "D:\Maps For Game" || exit /b 1"#,
+            r#""C:\SDK Tools\bin\vbsp.exe" -game "C:\Program Files (x86)\Steam\steamapps\common\MyGame" "D:\Maps For Game\level_01.vmf""#,
+            "if %errorlevel% neq 0 exit /b %errorlevel%",
+            "endlocal",
+            "",
+        ]
+        .join("\r\n")
+    );
+
+    assert_eq!(
+        script::render(&[command], ShellKind::PowerShell),
+        [
+            "$ErrorActionPreference = 'Stop'",
+            "",
+            "# VBSP",
+            r"Push-Location -LiteralPath 'D:\Maps For Game'",
+            "$savedEnv = @{}",
+            "$savedEnv['VPROJECT'] = $env:VPROJECT",
+            "$env:VPROJECT = '100%'",
+            r"& 'C:\SDK Tools\bin\vbsp.exe' -game 'C:\Program Files (x86)\Steam\steamapps\common\MyGame' 'D:\Maps For Game\level_01.vmf'",
+            "$env:VPROJECT = $savedEnv['VPROJECT']",
+            "Pop-Location",
+            "if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+/// Test 9.3: Verifies that a generated `sh` script passes arguments unchanged, runs each step
+/// in its own directory and stops at a failed step.
+#[cfg(unix)]
+#[test]
+fn test_posix_script_runs() {
+    let dir = std::env::temp_dir().join(format!("valve_compilers_script_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let work = dir.join("my maps");
+    std::fs::create_dir_all(&work).unwrap();
+
+    let step = |name, script: &str, args: &[&str]| CommandInfo {
+        name,
+        compiler_path: PathBuf::from("sh"),
+        args: ["-c", script, "sh"].iter().chain(args).map(|arg| arg.to_string()).collect(),
+        working_dir: work.clone(),
+        env: vec![("GREETING".to_string(), "it's me".to_string())],
+    };
+    // Without a working directory, a step runs where the script was started.
+    let in_start_dir = CommandInfo {
+        working_dir: PathBuf::new(),
+        env: Vec::new(),
+        ..step("WHERE", r#"echo "[$GREETING]" > where.txt"#, &[])
+    };
+    let commands = [
+        step("PRINT", r#"printf '%s|' "$GREETING" "$@" > args.txt"#, &["a b", "", "it's", "$HOME", "*"]),
+        in_start_dir,
+        step("FAIL", "exit 7", &[]),
+        step("NEVER", "touch never.txt", &[]),
+    ];
+    let path = dir.join(format!("compile.{}", ShellKind::Posix.extension()));
+    script::save(&path, &commands, ShellKind::Posix).unwrap();
+
+    let status = std::process::Command::new(&path).current_dir(&dir).status().unwrap();
+    assert_eq!(status.code(), Some(7));
+    assert_eq!(std::fs::read_to_string(work.join("args.txt")).unwrap(), "it's me|a b||it's|$HOME|*|");
+    assert_eq!(std::fs::read_to_string(dir.join("where.txt")).unwrap(), "[]\n");
+    assert!(!work.join("never.txt").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,210 @@
+use valve_compilers::keyvalues::{self, KeyValuesError};
+use valve_compilers::steam::{self, SteamError};
+use valve_compilers::CompilerContext;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Creates an empty fixture directory unique to the calling test.
+fn fixture_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("valve_compilers_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// Builds a Steam root whose `libraryfolders.vdf` points at a second library that holds TF2.
+fn steam_fixture(root: &Path) -> PathBuf {
+    let steam = root.join("Steam");
+    let library = root.join("Library Two");
+    let vdf = format!(
+        r#""libraryfolders"
+{{
+    "contentstatsid"    "123456789"
+    "0"
+    {{
+        "path"      "{steam}"
+        "apps" {{ "730" "1000" }}
+    }}
+    "1"
+    {{
+        "path"      "{library}"
+    }}
+}}"#,
+        steam = steam.display().to_string().replace('\\', "\\\\"),
+        library = library.display().to_string().replace('\\', "\\\\"),
+    );
+    write(&steam.join("steamapps/libraryfolders.vdf"), &vdf);
+    write(
+        &library.join("steamapps/appmanifest_440.acf"),
+        r#""AppState" { "appid" "440" "name" "Team Fortress 2" "installdir" "Team Fortress 2" }"#,
+    );
+    let tf2 = library.join("steamapps/common/Team Fortress 2");
+    write(&tf2.join("tf/gameinfo.txt"), "\"GameInfo\" {}");
+    write(&tf2.join("hl2/gameinfo.txt"), "\"GameInfo\" {}");
+    fs::create_dir_all(tf2.join("bin")).unwrap();
+    steam
+}
+
+/// Test 7.1: Verifies KeyValues parsing of nested blocks, comments, escapes and errors.
+#[test]
+fn test_keyvalues_parsing() {
+    let document = keyvalues::parse(
+        r#"
+        // A comment
+        "Root"
+        {
+            "Path"  "C:\\Program Files (x86)\\Steam"
+            unquoted value
+            "Raw"   "D:\Games"
+            "Nested" { "key" "a \"quoted\" word" }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let root = document.get_block("root").unwrap();
+    assert_eq!(root.get_str("path"), Some(r"C:\Program Files (x86)\Steam"));
+    assert_eq!(root.get_str("unquoted"), Some("value"));
+    assert_eq!(root.get_str("Raw"), Some(r"D:\Games"));
+    assert_eq!(root.get_block("Nested").unwrap().get_str("key"), Some(r#"a "quoted" word"#));
+    assert_eq!(root.get_str("Nested"), None);
+
+    // Serialized documents read back unchanged, in both modes.
+    assert_eq!(keyvalues::parse(&keyvalues::serialize(&document)).unwrap(), document);
+    let raw = keyvalues::parse_unescaped(r#""Run" { "exe" "C:\tools\new\" }"#).unwrap();
+    assert_eq!(raw.get_block("Run").unwrap().get_str("exe"), Some(r"C:\tools\new\"));
+    assert_eq!(keyvalues::parse_unescaped(&keyvalues::serialize_unescaped(&raw)).unwrap(), raw);
+
+    assert_eq!(keyvalues::parse(r#""Root" { "key" "value""#), Err(KeyValuesError::UnexpectedEof));
+    assert_eq!(keyvalues::parse("\"Root\"\n{\n \"key\" \"value"), Err(KeyValuesError::UnterminatedString { line: 3 }));
+    assert_eq!(
+        keyvalues::parse("\"key\" \"value\"\n}"),
+        Err(KeyValuesError::UnexpectedToken { line: 2, token: "}".to_string() })
+    );
+}
+
+/// Test 7.2: Verifies library folder discovery for both `libraryfolders.vdf` layouts.
+#[test]
+fn test_library_folders() {
+    let root = fixture_dir("steam_libraries");
+    let steam = steam_fixture(&root);
+    assert_eq!(steam::library_folders(&steam).unwrap(), vec![steam.clone(), root.join("Library Two")]);
+
+    let legacy = root.join("Legacy");
+    write(&legacy.join("steamapps/libraryfolders.vdf"), "\"LibraryFolders\" { \"TimeNextStatsReport\" \"1\" \"1\" \"E:\\\\Games\" }");
+    assert_eq!(steam::library_folders(&legacy).unwrap(), vec![legacy.clone(), PathBuf::from(r"E:\Games")]);
+
+    let empty = root.join("Empty");
+    assert_eq!(steam::library_folders(&empty).unwrap(), vec![empty.clone()]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test 7.3: Verifies that a context is derived from a game installed in a secondary library.
+#[test]
+fn test_context_from_steam_root() {
+    let root = fixture_dir("steam_context");
+    let steam = steam_fixture(&root);
+    let tf2 = root.join("Library Two/steamapps/common/Team Fortress 2");
+
+    let manifest = steam::find_app(&steam, 440).unwrap();
+    assert_eq!(manifest.name, "Team Fortress 2");
+    assert_eq!(manifest.install_path(), tf2);
+
+    let context = CompilerContext::from_steam_root(&steam, 440, Some(PathBuf::from("/maps/ctf_test.vmf"))).unwrap();
+    assert_eq!(context.bin_dir, tf2.join("bin"));
+    assert_eq!(context.game_dir, tf2.join("tf"));
+    assert_eq!(context.map_name, "ctf_test");
+
+    // Unknown App IDs fall back to the first non-`hl2` directory with a gameinfo.txt.
+    write(&root.join("Library Two/steamapps/appmanifest_999.acf"), r#""AppState" { "installdir" "Team Fortress 2" }"#);
+    let context = CompilerContext::from_steam_root(&steam, 999, None).unwrap();
+    assert_eq!(context.game_dir, tf2.join("tf"));
+
+    assert!(matches!(CompilerContext::from_steam_root(&steam, 730, None), Err(SteamError::AppNotInstalled(730))));
+
+    write(&steam.join("steamapps/appmanifest_730.acf"), r#""AppState" { "installdir" "#);
+    assert!(matches!(CompilerContext::from_steam_root(&steam, 730, None), Err(SteamError::Parse { .. })));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+/// Test 7.4: Verifies `gameinfo.txt` parsing, including conditionals and search paths.
+#[test]
+fn test_gameinfo_parsing() {
+    use valve_compilers::gameinfo::{GameInfo, SearchPath};
+
+    let info = GameInfo::parse(
+        r#"
+        "GameInfo"
+        {
+            game        "Counter-Strike: Global Offensive"
+            title       "COUNTER-STRIKE'"
+            type        multiplayer_only
+            nomodels 1 [$WIN32]
+            FileSystem
+            {
+                SteamAppId      730     // This will mount all the GCFs we need (240=CS:S, 220=HL2).
+                ToolsAppId      211     // Tools will load this (ie: source SDK caches) to get things like materials\debug, materials\editor, etc.
+                SearchPaths
+                {
+                    Game+Mod    csgo/addons/*
+                    Game        |gameinfo_path|.
+                    Platform    |all_source_engine_paths|platform
+                }
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(info.game, "Counter-Strike: Global Offensive");
+    assert_eq!(info.title.as_deref(), Some("COUNTER-STRIKE'"));
+    assert_eq!(info.steam_app_id, Some(730));
+    assert_eq!(info.tools_app_id, Some(211));
+    assert_eq!(info.search_paths[0], SearchPath { ids: vec!["Game".into(), "Mod".into()], path: "csgo/addons/*".into() });
+
+    let game_dir = Path::new("/games/csgo/csgo");
+    assert_eq!(info.search_paths[1].resolve(game_dir), Path::new("/games/csgo/csgo/."));
+    assert_eq!(info.search_paths[2].resolve(game_dir), Path::new("/games/csgo/platform"));
+
+    assert!(matches!(GameInfo::parse("\"NotGameInfo\" {}"), Err(valve_compilers::GameInfoError::MissingGameInfo)));
+}
+
+/// Test 7.5: Verifies App ID discovery from `gameinfo.txt` and context-aware validation.
+#[test]
+fn test_app_id_discovery() {
+    use valve_compilers::{Compiler, CompilerArg, GameInfoError};
+    use valve_compilers::vrad::{Vrad, VradArg};
+
+    let root = fixture_dir("gameinfo");
+    let mut context = CompilerContext::new(None, Some(root.join("csgo")), None, None);
+    assert!(matches!(context.detect_app_id(), Err(GameInfoError::Io { .. })));
+
+    write(&root.join("csgo/gameinfo.txt"), r#""GameInfo" { "FileSystem" { "SteamAppId" "730" } }"#);
+    assert_eq!(context.detect_app_id().unwrap(), Some(730));
+    assert_eq!(context.app_id, Some(730));
+
+    let mut vrad = Vrad::default();
+    vrad.add_arg(VradArg::Both);
+    assert!(!VradArg::Both.is_compatible_with_context(&context));
+    assert_eq!(vrad.validate_for_context(&context).len(), 1);
+
+    // Without a known App ID, compatibility is not checked.
+    context.app_id = None;
+    assert!(VradArg::Both.is_compatible_with_context(&context));
+    assert!(vrad.validate_for_context(&context).is_empty());
+
+    // Contexts created from Steam already know their App ID.
+    let steam = steam_fixture(&root);
+    let context = CompilerContext::from_steam_root(&steam, 440, None).unwrap();
+    assert_eq!(context.app_id, Some(440));
+    assert!(vrad.validate_for_context(&context).is_empty());
+
+    fs::remove_dir_all(&root).unwrap();
+}